@@ -0,0 +1,115 @@
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Usage {
+    pub allocations: usize,
+    pub bytes: usize,
+    pub peak: usize,
+}
+
+#[cfg(feature = "profile-alloc")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    static BYTES: AtomicUsize = AtomicUsize::new(0);
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counting;
+
+    #[global_allocator]
+    static GLOBAL: Counting = Counting;
+
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size, Ordering::Relaxed);
+        grow(size);
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            // only growth counts as an allocation, of the added bytes; a shrink just releases memory
+            if !new_ptr.is_null() {
+                if new_size > layout.size() {
+                    record(new_size - layout.size());
+                } else {
+                    CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+                }
+            }
+            new_ptr
+        }
+    }
+
+    pub fn start() -> (usize, usize, usize) {
+        let current = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(current, Ordering::Relaxed);
+        (ALLOCATIONS.load(Ordering::Relaxed), BYTES.load(Ordering::Relaxed), current)
+    }
+
+    pub fn finish((allocations, bytes, current): (usize, usize, usize)) -> super::Usage {
+        super::Usage {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+            bytes: BYTES.load(Ordering::Relaxed) - bytes,
+            peak: PEAK.load(Ordering::Relaxed) - current,
+        }
+    }
+}
+
+#[cfg(feature = "profile-alloc")]
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Usage) {
+    let start = counting::start();
+    let result = f();
+    (result, counting::finish(start))
+}
+
+#[cfg(not(feature = "profile-alloc"))]
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Usage) {
+    (f(), Usage::default())
+}
+
+pub fn report(day: &str, part: &str, usage: Usage) {
+    if cfg!(feature = "profile-alloc") {
+        println!("{} {} allocations {} bytes {} peak {}", day, part, usage.allocations, usage.bytes, usage.peak);
+    }
+}
+
+#[cfg(all(test, feature = "profile-alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let (data, usage) = measure(|| vec![1u8; 64]);
+        assert_eq!(data.len(), 64);
+        assert!(usage.allocations > 0);
+        assert!(usage.bytes >= 64);
+        assert!(usage.peak >= 64);
+    }
+}
@@ -0,0 +1,192 @@
+use std::io::{self, BufRead};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::env;
+
+const DEFAULT_YEAR: &str = "2023";
+const CONFIG_FILE: &str = ".aoc-inputs";
+const MANIFEST_FILE: &str = "manifest.txt";
+
+struct Store {
+    dir: PathBuf,
+}
+
+fn read_config(path: &Path) -> io::Result<(Option<PathBuf>, Option<String>)> {
+    let base = path.parent().unwrap_or(Path::new("."));
+    let mut root = None;
+    let mut year = None;
+    for line in fs::read_to_string(path)?.lines() {
+        match line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+            Some(("root", value)) => root = Some(base.join(value)),
+            Some(("year", value)) => year = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Ok((root, year))
+}
+
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, b| (hash ^ *b as u64).wrapping_mul(0x100000001b3))
+}
+
+impl Store {
+    fn discover() -> io::Result<Option<Store>> {
+        Self::discover_from(&env::current_dir()?, env::var_os("AOC_INPUTS").map(PathBuf::from), env::var("AOC_YEAR").ok())
+    }
+
+    fn discover_from(start: &Path, mut root: Option<PathBuf>, mut year: Option<String>) -> io::Result<Option<Store>> {
+        if root.is_none() {
+            for dir in start.ancestors() {
+                let config = dir.join(CONFIG_FILE);
+                if config.is_file() {
+                    let (config_root, config_year) = read_config(&config)?;
+                    root = Some(config_root.unwrap_or_else(|| dir.join("inputs")));
+                    year = year.or(config_year);
+                    break;
+                }
+                if dir.join("inputs").is_dir() {
+                    root = Some(dir.join("inputs"));
+                    break;
+                }
+            }
+        }
+        Ok(root.map(|root| Store { dir: root.join(year.as_deref().unwrap_or(DEFAULT_YEAR)) }))
+    }
+
+    fn file_name(day: &str, profile: Option<&str>) -> String {
+        match profile {
+            Some(profile) => format!("{}.{}.txt", day, profile),
+            None => format!("{}.txt", day),
+        }
+    }
+
+    fn manifest(&self) -> io::Result<Vec<(String, String)>> {
+        let manifest = self.dir.join(MANIFEST_FILE);
+        if !manifest.is_file() {
+            return Ok(Vec::new());
+        }
+        Ok(fs::read_to_string(manifest)?.lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(name, sum)| (name.to_string(), sum.trim().to_string()))
+            .collect())
+    }
+
+    // files without a manifest entry are accepted, entries are only written by record
+    fn verify(&self, file_name: &str, data: &[u8]) -> io::Result<()> {
+        let sum = format!("{:016x}", checksum(data));
+        match self.manifest()?.into_iter().find(|(name, _)| name == file_name) {
+            Some((_, expected)) if expected != sum => Err(io::Error::new(io::ErrorKind::InvalidData, format!("checksum mismatch for {}", file_name))),
+            _ => Ok(()),
+        }
+    }
+
+    fn record(&self, file_name: &str, data: &[u8]) -> io::Result<()> {
+        let mut entries = self.manifest()?;
+        entries.retain(|(name, _)| name != file_name);
+        entries.push((file_name.to_string(), format!("{:016x}", checksum(data))));
+        let text = entries.iter().map(|(name, sum)| format!("{} {}\n", name, sum)).collect::<String>();
+        fs::write(self.dir.join(MANIFEST_FILE), text)
+    }
+
+    fn load(&self, day: &str, profile: Option<&str>) -> io::Result<Option<(String, Vec<u8>)>> {
+        let file_name = Self::file_name(day, profile);
+        let path = self.dir.join(&file_name);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Ok(Some((file_name, fs::read(&path)?)))
+    }
+
+    fn read(&self, day: &str, profile: Option<&str>) -> io::Result<Option<Vec<String>>> {
+        let Some((file_name, data)) = self.load(day, profile)? else {
+            return Ok(None);
+        };
+        self.verify(&file_name, &data)?;
+        io::BufReader::new(data.as_slice()).lines().collect::<io::Result<_>>().map(Some)
+    }
+}
+
+// input of the given day from the discovered store, None when there is no store or no such file
+pub fn read_store(day: &str, profile: Option<&str>) -> io::Result<Option<Vec<String>>> {
+    match Store::discover()? {
+        Some(store) => store.read(day, profile),
+        None => Ok(None),
+    }
+}
+
+// stores the checksum of a stored input in the manifest, replacing any previous entry
+pub fn record_store(day: &str, profile: Option<&str>) -> io::Result<()> {
+    let not_found = || io::Error::new(io::ErrorKind::NotFound, "stored input not found");
+    let store = Store::discover()?.ok_or_else(not_found)?;
+    let (file_name, data) = store.load(day, profile)?.ok_or_else(not_found)?;
+    store.record(&file_name, &data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-store-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_config() {
+        let dir = temp_dir("config");
+        fs::write(dir.join(CONFIG_FILE), "# comment\nroot = data\nyear=2022\n").unwrap();
+        assert_eq!(read_config(&dir.join(CONFIG_FILE)).unwrap(), (Some(dir.join("data")), Some("2022".to_string())));
+        fs::write(dir.join(CONFIG_FILE), "unknown=1\n").unwrap();
+        assert_eq!(read_config(&dir.join(CONFIG_FILE)).unwrap(), (None, None));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = temp_dir("discover");
+        let nested = dir.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(Store::discover_from(&nested, Some(dir.join("env")), Some("2021".to_string())).unwrap().unwrap().dir, dir.join("env").join("2021"));
+        fs::create_dir(dir.join("inputs")).unwrap();
+        assert_eq!(Store::discover_from(&nested, None, None).unwrap().unwrap().dir, dir.join("inputs").join(DEFAULT_YEAR));
+        fs::write(dir.join("a").join(CONFIG_FILE), "root=store\nyear=2022\n").unwrap();
+        assert_eq!(Store::discover_from(&nested, None, None).unwrap().unwrap().dir, dir.join("a").join("store").join("2022"));
+        assert_eq!(Store::discover_from(&nested, None, Some("2021".to_string())).unwrap().unwrap().dir, dir.join("a").join("store").join("2021"));
+        fs::write(dir.join("a").join(CONFIG_FILE), "year=2022\n").unwrap();
+        assert_eq!(Store::discover_from(&nested, None, None).unwrap().unwrap().dir, dir.join("a").join("inputs").join("2022"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_profiles() {
+        let dir = temp_dir("profiles");
+        let store = Store { dir: dir.clone() };
+        fs::write(dir.join(Store::file_name("day00", None)), "main\n").unwrap();
+        fs::write(dir.join(Store::file_name("day00", Some("example"))), "example\n").unwrap();
+        assert_eq!(Store::file_name("day00", Some("example")), "day00.example.txt");
+        assert_eq!(store.read("day00", None).unwrap(), Some(vec!["main".to_string()]));
+        assert_eq!(store.read("day00", Some("example")).unwrap(), Some(vec!["example".to_string()]));
+        assert_eq!(store.read("day00", Some("missing")).unwrap(), None);
+        assert!(!dir.join(MANIFEST_FILE).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest() {
+        let dir = temp_dir("manifest");
+        let store = Store { dir: dir.clone() };
+        let file_name = Store::file_name("day00", None);
+        fs::write(dir.join(&file_name), "1\n").unwrap();
+        store.record(&file_name, b"1\n").unwrap();
+        store.record("other.txt", b"").unwrap();
+        assert_eq!(store.read("day00", None).unwrap(), Some(vec!["1".to_string()]));
+        fs::write(dir.join(&file_name), "2\n").unwrap();
+        assert_eq!(store.read("day00", None).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        store.record(&file_name, b"2\n").unwrap();
+        assert_eq!(store.read("day00", None).unwrap(), Some(vec!["2".to_string()]));
+        assert_eq!(store.manifest().unwrap().len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
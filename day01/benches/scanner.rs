@@ -0,0 +1,43 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use day01::scanner::Scanner;
+use day01::task::{task1_with, task2_with, Strictness};
+use day01::vocabulary::DigitVocabulary;
+
+const WORDS: [&str; 12] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "xyz", "qrst", "abc"];
+
+fn document(lines: usize) -> String {
+    let mut seed = 0x2545f4914f6cdd1du64;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+    let mut data = String::new();
+    for _ in 0..lines {
+        for _ in 0..next() % 8 + 2 {
+            match next() % 3 {
+                0 => data.push((b'0' + (next() % 10) as u8) as char),
+                _ => data.push_str(WORDS[(next() % WORDS.len() as u64) as usize]),
+            }
+        }
+        data.push('\n');
+    }
+    data
+}
+
+fn bench(c: &mut Criterion) {
+    let data = document(100_000);
+    let lines = data.lines().collect::<Vec<_>>();
+    let vocabulary = DigitVocabulary::english();
+    let mut group = c.benchmark_group("calibration");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("task1", |b| b.iter(|| task1_with(&lines, Strictness::Lenient)));
+    group.bench_function("scan_numerals", |b| b.iter(|| Scanner::numerals().scan_bytes(data.as_bytes())));
+    group.bench_function("task2", |b| b.iter(|| task2_with(&lines, &vocabulary, Strictness::Lenient)));
+    group.bench_function("scan_words", |b| b.iter(|| Scanner::words(&vocabulary).scan_bytes(data.as_bytes())));
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,3 @@
+pub mod scanner;
+pub mod task;
+pub mod vocabulary;
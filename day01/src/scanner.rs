@@ -0,0 +1,217 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use memchr::memchr;
+use crate::task::{Error, Result, Strictness};
+use crate::vocabulary::DigitVocabulary;
+
+const CHUNK_SIZE: usize = 1 << 16;
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Summary {
+    pub sum: u64,
+    pub records: u64,
+    // 1-based numbers of the records without digits
+    pub skipped: Vec<u64>,
+}
+
+impl Summary {
+    pub fn check(self, strictness: Strictness) -> Result<Self> {
+        match self.skipped.first() {
+            Some(line) if strictness == Strictness::Strict => Err(Error::NoDigits(*line as usize)),
+            _ => Ok(self),
+        }
+    }
+}
+
+// same format as Calibration
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.sum)?;
+        if !self.skipped.is_empty() {
+            let lines = self.skipped.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            write!(f, " (skipped {} lines: {})", self.skipped.len(), lines.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Scanner<'a> {
+    vocabulary: Option<&'a DigitVocabulary>,
+}
+
+#[derive(Debug, Default)]
+struct Record {
+    state: usize,
+    pos: u64,
+    started: bool,
+    first: Option<(u64, u32)>,
+    last: Option<(u64, u32)>,
+}
+
+impl Record {
+    fn found(&mut self, start: u64, value: u32) {
+        if self.first.is_none_or(|(s, _)| start < s) {
+            self.first = Some((start, value));
+        }
+        if self.last.is_none_or(|(s, _)| start >= s) {
+            self.last = Some((start, value));
+        }
+    }
+
+    fn flush(&mut self, summary: &mut Summary) {
+        match (self.first, self.last) {
+            (Some((_, first)), Some((_, last))) => summary.sum += (first * 10 + last) as u64,
+            _ => summary.skipped.push(summary.records + 1),
+        }
+        summary.records += 1;
+        *self = Record { pos: self.pos, ..Record::default() };
+    }
+}
+
+impl<'a> Scanner<'a> {
+    pub fn numerals() -> Self {
+        Self { vocabulary: None }
+    }
+
+    pub fn words(vocabulary: &'a DigitVocabulary) -> Self {
+        Self { vocabulary: Some(vocabulary) }
+    }
+
+    fn feed_numerals(record: &mut Record, segment: &[u8]) {
+        if record.first.is_none() {
+            if let Some(i) = segment.iter().position(u8::is_ascii_digit) {
+                record.first = Some((record.pos + i as u64, (segment[i] - b'0') as u32));
+            }
+        }
+        if let Some(i) = segment.iter().rposition(u8::is_ascii_digit) {
+            record.last = Some((record.pos + i as u64, (segment[i] - b'0') as u32));
+        }
+    }
+
+    fn feed_words(vocabulary: &DigitVocabulary, record: &mut Record, segment: &[u8]) {
+        for (i, b) in segment.iter().enumerate() {
+            record.state = vocabulary.step(record.state, *b);
+            let end = record.pos + i as u64 + 1;
+            for (len, value) in vocabulary.matches(record.state) {
+                record.found(end - len as u64, value);
+            }
+        }
+    }
+
+    fn feed(&self, record: &mut Record, summary: &mut Summary, mut chunk: &[u8]) {
+        while !chunk.is_empty() {
+            let newline = memchr(b'\n', chunk);
+            let segment = &chunk[..newline.unwrap_or(chunk.len())];
+            match self.vocabulary {
+                Some(vocabulary) => Self::feed_words(vocabulary, record, segment),
+                None => Self::feed_numerals(record, segment),
+            }
+            record.started |= !segment.is_empty();
+            record.pos += segment.len() as u64;
+            match newline {
+                Some(i) => {
+                    record.pos += 1;
+                    record.flush(summary);
+                    chunk = &chunk[i + 1..];
+                },
+                None => break,
+            }
+        }
+    }
+
+    pub fn scan_bytes(&self, data: &[u8]) -> Summary {
+        let mut summary = Summary::default();
+        let mut record = Record::default();
+        self.feed(&mut record, &mut summary, data);
+        if record.started {
+            record.flush(&mut summary);
+        }
+        summary
+    }
+
+    pub fn scan_reader<R: Read>(&self, mut reader: R) -> io::Result<Summary> {
+        let mut summary = Summary::default();
+        let mut record = Record::default();
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => self.feed(&mut record, &mut summary, &buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => return Err(e),
+            }
+        }
+        if record.started {
+            record.flush(&mut summary);
+        }
+        Ok(summary)
+    }
+
+    pub fn scan_file<P: AsRef<Path>>(&self, path: P) -> io::Result<Summary> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(Summary::default());
+        }
+        // SAFETY: the input file is assumed not to change or be truncated while it is mapped
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(self.scan_bytes(&map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{task1_with, task2_with};
+
+    const DATA: &str = "two1nine\neightwothree\nabcone2threexyz\n\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen\nnothing";
+
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.0.len()).min(3);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn scan_matches_tasks() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let vocabulary = DigitVocabulary::english();
+        let task1 = task1_with(&lines, Strictness::Lenient).unwrap();
+        let task2 = task2_with(&lines, &vocabulary, Strictness::Lenient).unwrap();
+        for data in [DATA.to_string(), format!("{}\n", DATA)] {
+            let numerals = Scanner::numerals();
+            let words = Scanner::words(&vocabulary);
+            let expected1 = Summary { sum: task1.sum as u64, records: 9, skipped: task1.skipped.iter().map(|n| *n as u64).collect() };
+            let expected2 = Summary { sum: task2.sum as u64, records: 9, skipped: task2.skipped.iter().map(|n| *n as u64).collect() };
+            assert_eq!(expected1.to_string(), task1.to_string());
+            assert_eq!(numerals.scan_bytes(data.as_bytes()), expected1);
+            assert_eq!(numerals.scan_reader(Trickle(data.as_bytes())).unwrap(), expected1);
+            assert_eq!(words.scan_bytes(data.as_bytes()), expected2);
+            assert_eq!(words.scan_reader(Trickle(data.as_bytes())).unwrap(), expected2);
+        }
+    }
+
+    #[test]
+    fn scan_empty() {
+        assert_eq!(Scanner::numerals().scan_bytes(b""), Summary::default());
+        assert_eq!(Scanner::numerals().scan_bytes(b"\n"), Summary { sum: 0, records: 1, skipped: vec![1] });
+    }
+
+    #[test]
+    fn scan_strictness() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let summary = Scanner::numerals().scan_bytes(DATA.as_bytes());
+        assert_eq!(summary.clone().check(Strictness::Strict), Err(Error::NoDigits(2)));
+        assert_eq!(task1_with(&lines, Strictness::Strict), Err(Error::NoDigits(2)));
+        assert_eq!(summary.clone().check(Strictness::Lenient), Ok(summary));
+        let summary = Scanner::numerals().scan_bytes(b"a1\nb2c\n");
+        assert_eq!(summary.clone().check(Strictness::Strict), Ok(summary));
+    }
+}
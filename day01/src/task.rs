@@ -1,44 +1,92 @@
+use std::fmt;
+use std::sync::OnceLock;
+use crate::vocabulary::{DigitKind, DigitToken, DigitVocabulary};
+
 #[derive(thiserror::Error, Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Error {
     #[error("Parse error")]
-    ParseError
+    ParseError,
+    #[error("No digits on line {0}")]
+    NoDigits(usize),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Strictness {
+    Strict,
+    Lenient,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Calibration {
+    pub sum: u32,
+    pub skipped: Vec<usize>,
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.sum)?;
+        if !self.skipped.is_empty() {
+            let lines = self.skipped.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            write!(f, " (skipped {} lines: {})", self.skipped.len(), lines.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn digit_tokens(line: &str) -> impl Iterator<Item = DigitToken> + '_ {
+    static ENGLISH: OnceLock<DigitVocabulary> = OnceLock::new();
+    ENGLISH.get_or_init(DigitVocabulary::english).tokens(line)
+}
+
+pub fn first_last<I: IntoIterator<Item = DigitToken>>(tokens: I) -> Option<u32> {
+    let mut tokens = tokens.into_iter();
+    let first = tokens.next()?.value;
+    let last = tokens.last().map_or(first, |t| t.value);
+    Some(first * 10 + last)
+}
+
+// the numeral tokens of digit_tokens, without running the word automaton
+pub fn numeral_tokens(line: &str) -> impl Iterator<Item = DigitToken> + '_ {
+    line.bytes().enumerate().filter(|(_, b)| b.is_ascii_digit())
+        .map(|(i, b)| DigitToken { value: (b - b'0') as u32, span: i..i + 1, kind: DigitKind::Numeral })
+}
+
 fn extract_number(s: &str) -> Result<u32> {
-    let first = s.bytes().find(|b| b.is_ascii_digit()).map(|b| (b - b'0') as u32).ok_or(Error::ParseError)?;
-    let last = s.bytes().rev().find(|b| b.is_ascii_digit()).map(|b| (b - b'0') as u32).ok_or(Error::ParseError)?;
-    Ok(first * 10 + last)
+    first_last(numeral_tokens(s)).ok_or(Error::ParseError)
 }
 
-fn split_number(s: &str) -> Result<u32> {
-    let literals = vec!["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
-    let numbers = vec!["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
-    let first = numbers.iter()
-    .enumerate()
-    .filter_map(|(x, n)| s.find(n).map(|i| (i, x)))
-    .chain(
-        literals.iter().enumerate().filter_map(|(x, n)| s.find(n).map(|i| (i, x)))
-    ).min()
-    .map(|(_, x)| x as u32).ok_or(Error::ParseError)?;
-
-    let last = numbers.iter()
-    .enumerate()
-    .filter_map(|(x, n)| s.rfind(n).map(|i| (i, x)))
-    .chain(
-        literals.iter().enumerate().filter_map(|(x, n)| s.rfind(n).map(|i| (i, x)))
-    ).max()
-    .map(|(_, x)| x as u32).ok_or(Error::ParseError)?;
-    return Ok(first * 10 + last);
+fn split_number(s: &str, vocabulary: &DigitVocabulary) -> Result<u32> {
+    first_last(vocabulary.tokens(s)).ok_or(Error::ParseError)
+}
+
+pub fn calibrate<S: AsRef<str>, F: Fn(&str) -> Result<u32>>(lines: &[S], strictness: Strictness, f: F) -> Result<Calibration> {
+    let mut calibration = Calibration::default();
+    for (i, line) in lines.iter().enumerate() {
+        match f(line.as_ref()) {
+            Ok(number) => calibration.sum += number,
+            Err(_) if strictness == Strictness::Lenient => calibration.skipped.push(i + 1),
+            Err(_) => return Err(Error::NoDigits(i + 1)),
+        }
+    }
+    Ok(calibration)
 }
 
 pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
-    Ok(lines.into_iter().map(|s| extract_number(s.as_ref()).unwrap()).sum())
+    task1_with(lines, Strictness::Strict).map(|c| c.sum)
+}
+
+pub fn task1_with<S: AsRef<str>>(lines: &[S], strictness: Strictness) -> Result<Calibration> {
+    calibrate(lines, strictness, extract_number)
 }
 
 pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
-    Ok(lines.into_iter().map(|s| split_number(s.as_ref()).unwrap()).sum())
+    task2_with(lines, &DigitVocabulary::english(), Strictness::Strict).map(|c| c.sum)
+}
+
+pub fn task2_with<S: AsRef<str>>(lines: &[S], vocabulary: &DigitVocabulary, strictness: Strictness) -> Result<Calibration> {
+    calibrate(lines, strictness, |s| split_number(s, vocabulary))
 }
 
 #[cfg(test)]
@@ -61,9 +109,10 @@ mod tests {
     }
     #[test]
     fn split_test() {
-        assert_eq!(split_number("9986fmfqhdmq8"), Ok(98));
-        assert_eq!(split_number("31eight"), Ok(38));
-        assert_eq!(split_number("vxnsvnine5seventhree2mksgsjbpkqdgbs"), Ok(92));
+        let vocabulary = DigitVocabulary::english();
+        assert_eq!(split_number("9986fmfqhdmq8", &vocabulary), Ok(98));
+        assert_eq!(split_number("31eight", &vocabulary), Ok(38));
+        assert_eq!(split_number("vxnsvnine5seventhree2mksgsjbpkqdgbs", &vocabulary), Ok(92));
     }
 
     #[test]
@@ -87,6 +136,58 @@ mod tests {
         4nineeightseven2
         zoneight234
         7pqrstsixteen";
-        assert_eq!(data.split('\n').map(|s| split_number(s).unwrap()).collect::<Vec<_>>(), &[29, 83, 13, 24, 42, 14, 76]);
+        let vocabulary = DigitVocabulary::english();
+        assert_eq!(data.split('\n').map(|s| split_number(s, &vocabulary).unwrap()).collect::<Vec<_>>(), &[29, 83, 13, 24, 42, 14, 76]);
+    }
+
+    #[test]
+    fn vocabulary_test() {
+        let vocabulary = DigitVocabulary::english();
+        assert_eq!(split_number("eightwo", &vocabulary), Ok(82));
+        assert_eq!(split_number("oneight", &vocabulary), Ok(18));
+        assert_eq!(split_number("xoh5", &vocabulary), Ok(55));
+        assert_eq!(split_number("xoh5", &vocabulary.with("oh", 0)), Ok(5));
+
+        let german = "eins 1\nzwei 2\ndrei 3\nvier 4\nneun 9".parse::<DigitVocabulary>().unwrap();
+        let mixed = DigitVocabulary::english().extend(&german);
+        assert_eq!(split_number("zweightxneun", &mixed), Ok(29));
+        assert_eq!(split_number("threeins", &mixed), Ok(31));
+        assert_eq!(split_number("thirdfirst", &DigitVocabulary::english_ordinals()), Ok(31));
+        assert_eq!("ten 10".parse::<DigitVocabulary>().err(), Some(Error::ParseError));
+    }
+
+    #[test]
+    fn strictness_test() {
+        let data = ["1abc2", "nothing", "pqr3stu8vwx", "", "two"];
+        assert_eq!(task1(&data), Err(Error::NoDigits(2)));
+        assert_eq!(task1_with(&data, Strictness::Lenient), Ok(Calibration { sum: 50, skipped: vec![2, 4, 5] }));
+        assert_eq!(task2_with(&data, &DigitVocabulary::english(), Strictness::Lenient), Ok(Calibration { sum: 72, skipped: vec![2, 4] }));
+        assert_eq!(task1_with(&data, Strictness::Lenient).unwrap().to_string(), "50 (skipped 3 lines: 2, 4, 5)");
+        assert_eq!(task1_with(&data[..1], Strictness::Lenient).unwrap().to_string(), "12");
+    }
+
+    #[test]
+    fn tokens_test() {
+        let tokens = digit_tokens("4eightwo1").collect::<Vec<_>>();
+        assert_eq!(tokens, vec![
+            DigitToken { value: 4, span: 0..1, kind: DigitKind::Numeral },
+            DigitToken { value: 8, span: 1..6, kind: DigitKind::Word },
+            DigitToken { value: 2, span: 5..8, kind: DigitKind::Word },
+            DigitToken { value: 1, span: 8..9, kind: DigitKind::Numeral },
+        ]);
+        assert_eq!(digit_tokens("a1b2c3").map(|t| t.value).sum::<u32>(), 6);
+        assert_eq!(digit_tokens("x7y").map(|t| t.span).collect::<Vec<_>>(), vec![1..2]);
+        assert_eq!(first_last(digit_tokens("abc")), None);
+        assert_eq!(first_last(digit_tokens("x7y")), Some(77));
+        for line in ["4eightwo1", "a1b2c3", "", "seveneightwone9"] {
+            let numerals = digit_tokens(line).filter(|t| t.kind == DigitKind::Numeral).collect::<Vec<_>>();
+            assert_eq!(numeral_tokens(line).collect::<Vec<_>>(), numerals);
+        }
+        let vocabulary = DigitVocabulary::english_ordinals().with("ei", 3);
+        let line = "xfirstwoneighthreeighth7";
+        assert_eq!(vocabulary.tokens(line).map(|t| (t.span, t.value)).collect::<Vec<_>>(), vec![
+            (1..6, 1), (5..8, 2), (7..10, 1), (9..11, 3), (9..14, 8), (9..15, 8),
+            (13..18, 3), (17..19, 3), (17..22, 8), (17..23, 8), (23..24, 7),
+        ]);
     }
 }
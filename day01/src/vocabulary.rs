@@ -0,0 +1,187 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::ops::Range;
+use std::str::FromStr;
+use crate::task::{Error, Result};
+
+const ENGLISH: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const ORDINALS: [&str; 10] = ["zeroth", "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitKind {
+    Numeral,
+    Word,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitToken {
+    pub value: u32,
+    pub span: Range<usize>,
+    pub kind: DigitKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pattern {
+    len: usize,
+    value: u32,
+    kind: DigitKind,
+}
+
+#[derive(Debug, Clone)]
+struct Automaton {
+    next: Vec<[u32; 256]>,
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn build(patterns: &[&[u8]]) -> Self {
+        let mut next = vec![[0u32; 256]];
+        let mut outputs = vec![Vec::new()];
+        let mut is_edge = vec![[false; 256]];
+        for (id, pattern) in patterns.iter().enumerate() {
+            let mut state = 0;
+            for b in pattern.iter() {
+                if !is_edge[state][*b as usize] {
+                    next.push([0; 256]);
+                    outputs.push(Vec::new());
+                    is_edge.push([false; 256]);
+                    is_edge[state][*b as usize] = true;
+                    next[state][*b as usize] = (next.len() - 1) as u32;
+                }
+                state = next[state][*b as usize] as usize;
+            }
+            outputs[state].push(id);
+        }
+
+        let mut fail = vec![0usize; next.len()];
+        let mut queue = (0..256).filter(|b| is_edge[0][*b]).map(|b| next[0][b] as usize).collect::<VecDeque<_>>();
+        while let Some(state) = queue.pop_front() {
+            for b in 0..256 {
+                if is_edge[state][b] {
+                    let child = next[state][b] as usize;
+                    fail[child] = next[fail[state]][b] as usize;
+                    let inherited = outputs[fail[child]].clone();
+                    outputs[child].extend(inherited);
+                    queue.push_back(child);
+                } else {
+                    next[state][b] = next[fail[state]][b];
+                }
+            }
+        }
+        Self { next, outputs }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DigitVocabulary {
+    words: Vec<(String, u32)>,
+    patterns: Vec<Pattern>,
+    max_len: usize,
+    automaton: Automaton,
+}
+
+impl DigitVocabulary {
+    pub fn new<I: IntoIterator<Item = (W, u32)>, W: Into<String>>(words: I) -> Self {
+        let words = words.into_iter().map(|(w, v)| (w.into(), v)).filter(|(w, _)| !w.is_empty()).collect::<Vec<(String, u32)>>();
+        let numerals = (0..10).map(|v| v.to_string()).collect::<Vec<_>>();
+        let all = numerals.iter().zip(0..).chain(words.iter().map(|(w, v)| (w, *v))).collect::<Vec<_>>();
+        let patterns = all.iter().enumerate()
+            .map(|(id, (w, v))| Pattern { len: w.len(), value: *v, kind: if id < 10 { DigitKind::Numeral } else { DigitKind::Word } })
+            .collect();
+        let max_len = all.iter().map(|(w, _)| w.len()).max().unwrap_or(0);
+        let automaton = Automaton::build(&all.iter().map(|(w, _)| w.as_bytes()).collect::<Vec<_>>());
+        Self { words, patterns, max_len, automaton }
+    }
+
+    pub fn english() -> Self {
+        Self::new(ENGLISH.into_iter().zip(0..))
+    }
+
+    pub fn english_ordinals() -> Self {
+        Self::new(ENGLISH.into_iter().zip(0..).chain(ORDINALS.into_iter().zip(0..)))
+    }
+
+    pub fn with(self, word: &str, value: u32) -> Self {
+        Self::new(self.words.into_iter().chain(std::iter::once((word.to_string(), value))))
+    }
+
+    pub fn extend(self, other: &DigitVocabulary) -> Self {
+        Self::new(self.words.into_iter().chain(other.words.iter().cloned()))
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> + '_ {
+        self.words.iter().map(|(w, v)| (w.as_str(), *v))
+    }
+
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Result<Self> {
+        let mut words = Vec::new();
+        for line in lines.iter().map(|s| s.as_ref().trim()).filter(|s| !s.is_empty() && !s.starts_with('#')) {
+            let (word, value) = line.rsplit_once(char::is_whitespace).ok_or(Error::ParseError)?;
+            let value = value.parse::<u32>().map_err(|_| Error::ParseError)?;
+            if value > 9 {
+                return Err(Error::ParseError);
+            }
+            words.push((word.trim().to_string(), value));
+        }
+        Ok(Self::new(words))
+    }
+
+    pub(crate) fn step(&self, state: usize, b: u8) -> usize {
+        self.automaton.next[state][b as usize] as usize
+    }
+
+    // (length, value) of every pattern ending in the given state
+    pub(crate) fn matches(&self, state: usize) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.automaton.outputs[state].iter().map(|id| (self.patterns[*id].len, self.patterns[*id].value))
+    }
+
+    pub fn tokens<'a>(&'a self, line: &'a str) -> Tokens<'a> {
+        Tokens { vocabulary: self, bytes: line.as_bytes(), pos: 0, state: 0, pending: BinaryHeap::new() }
+    }
+}
+
+// tokens ordered by (start, end); a match is held back only until no later match can start before it
+pub struct Tokens<'a> {
+    vocabulary: &'a DigitVocabulary,
+    bytes: &'a [u8],
+    pos: usize,
+    state: usize,
+    pending: BinaryHeap<Reverse<(usize, usize, usize)>>,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = DigitToken;
+
+    fn next(&mut self) -> Option<DigitToken> {
+        loop {
+            let done = self.pos == self.bytes.len();
+            if let Some(Reverse((start, _, _))) = self.pending.peek() {
+                if done || start + self.vocabulary.max_len <= self.pos {
+                    let Reverse((start, end, id)) = self.pending.pop()?;
+                    let Pattern { value, kind, .. } = self.vocabulary.patterns[id];
+                    return Some(DigitToken { value, span: start..end, kind });
+                }
+            } else if done {
+                return None;
+            }
+            self.state = self.vocabulary.step(self.state, self.bytes[self.pos]);
+            self.pos += 1;
+            for id in &self.vocabulary.automaton.outputs[self.state] {
+                self.pending.push(Reverse((self.pos - self.vocabulary.patterns[*id].len, self.pos, *id)));
+            }
+        }
+    }
+}
+
+impl Default for DigitVocabulary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl FromStr for DigitVocabulary {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        Self::from_lines(&s.lines().collect::<Vec<_>>())
+    }
+}
@@ -1,32 +1,87 @@
 mod input;
-mod task;
 
 use input::{read_file, read_stdin};
+use common::profile::{measure, report};
+use common::store::{read_store, record_store};
 use std::io;
 use std::env;
-use task::*;
+use day02::task::*;
 
 enum Mode {
     File(String),
-    Stdin
+    Stdin,
+    Store(Option<String>),
+}
+
+fn query(record: &GameRecord, name: &str, bag: &Bag) -> io::Result<()> {
+    match name {
+        "minimal-bag" => println!("{}", record.minimal_bag()),
+        "feasible" => println!("{}", record.feasible_games(bag).iter().map(|id| id.to_string()).collect::<Vec<_>>().join(" ")),
+        "infeasible" => {
+            for InfeasibleDraw { game, draw, excess } in record.infeasible_draws(bag) {
+                let sole = if record.sole_blocking_draw(game, bag) == Some(draw) { " (sole blocker)" } else { "" };
+                println!("game {} draw {} exceeds by {}{}", game, draw + 1, Bag(excess), sole);
+            }
+        },
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unknown query {}", name))),
+    }
+    Ok(())
 }
 
 fn main() -> io::Result<()> {
-    let mode = env::args().nth(1).map(|arg| Mode::File(arg)).unwrap_or(Mode::Stdin);
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let bag = match args.iter().position(|arg| arg == "--bag" || arg == "--bag-file") {
+        Some(i) if i + 1 < args.len() => {
+            let flag = args.remove(i);
+            let value = args.remove(i);
+            let bag = if flag == "--bag" { value.parse() } else { Bag::from_lines(&read_file(&value)?) };
+            bag.map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, format!("{} {}: {}", flag, value, error)))?
+        },
+        _ => Bag::default(),
+    };
+    let query_name = match args.first() {
+        Some(arg) if arg == "query" && args.len() > 1 => Some(args.drain(..2).nth(1).unwrap()),
+        _ => None,
+    };
+    let mut args = args.into_iter();
+    let mode = match args.next() {
+        Some(arg) if arg == "--profile" => match args.next() {
+            Some(profile) => Mode::Store(Some(profile)),
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "--profile needs a name")),
+        },
+        Some(arg) if arg == "--record" => return record_store(env!("CARGO_PKG_NAME"), args.next().as_deref()),
+        Some(arg) if arg == "-" => Mode::Stdin,
+        Some(arg) => Mode::File(arg),
+        None => Mode::Store(None),
+    };
     let data = match mode {
         Mode::File(file_path) => read_file(&file_path)?,
-        Mode::Stdin => read_stdin()?
+        Mode::Stdin => read_stdin()?,
+        Mode::Store(profile) => match read_store(env!("CARGO_PKG_NAME"), profile.as_deref())? {
+            Some(data) => data,
+            None if profile.is_none() => read_stdin()?,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "stored input not found")),
+        },
     };
 
-    match task1(&data) {
+    if let Some(name) = query_name {
+        let record = GameRecord::from_lines(&data).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        return query(&record, &name, &bag);
+    }
+
+    let (result, usage) = measure(|| task1_with(&data, &bag));
+    match result {
         Ok(result) => println!("result1 {}", result),
         Err(error) => println!("error {}", error)
     }
+    report(env!("CARGO_PKG_NAME"), "task1", usage);
 
-    match task2(&data) {
+    let (result, usage) = measure(|| task2_with(&data, &bag));
+    match result {
         Ok(result) => println!("result2 {}", result),
         Err(error) => println!("error {}", error)
     }
+    report(env!("CARGO_PKG_NAME"), "task2", usage);
 
     Ok(())
 }
@@ -1,86 +1,262 @@
-#[derive(thiserror::Error, Debug, Clone, Copy, Eq, PartialEq)]
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
     #[error("Parse error")]
     ParseError,
+    #[error("Duplicate color {0}")]
+    DuplicateColor(String),
+    #[error("Empty draw")]
+    EmptyDraw,
+    #[error("Game {0} has no draws")]
+    NoDraws(u32),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn is_valid_game(cubes: &[u32; 3]) -> bool {
-    let max_possible = &[12, 13, 14];
-    cubes.iter().zip(max_possible.iter()).all(|(a, b)| a <= b)
+pub type Cubes = BTreeMap<String, u32>;
+
+// a draw shows at least one colour and a game has at least one draw, so both print as parseable text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Draw(Cubes);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    id: u32,
+    draws: Vec<Draw>,
 }
 
-fn color_number(color: &str) -> Result<usize> {
-    match color {
-        "red" => Ok(0),
-        "green" => Ok(1),
-        "blue" => Ok(2),
-        _ => Err(Error::ParseError)
+impl Draw {
+    pub fn new(cubes: Cubes) -> Result<Self> {
+        if cubes.is_empty() {
+            return Err(Error::EmptyDraw);
+        }
+        Ok(Draw(cubes))
+    }
+
+    pub fn cubes(&self) -> &Cubes {
+        &self.0
     }
 }
 
-fn power(cubes: &[u32; 3]) -> u32 {
-    cubes.iter().product()
+impl Game {
+    pub fn new(id: u32, draws: Vec<Draw>) -> Result<Self> {
+        if draws.is_empty() {
+            return Err(Error::NoDraws(id));
+        }
+        Ok(Game { id, draws })
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn draws(&self) -> &[Draw] {
+        &self.draws
+    }
 }
 
-fn game_power(game: &[[u32; 3]]) -> u32 {
-    let max_cubes = game.iter().fold([0u32; 3], |mut acc, g| {
-        g.iter().enumerate().for_each(|(i, x)| acc[i] = acc[i].max(*x));
-        acc
-    });
-    power(&max_cubes)
+fn format_cubes(f: &mut fmt::Formatter<'_>, cubes: &Cubes) -> fmt::Result {
+    let cubes = cubes.iter().map(|(color, count)| format!("{} {}", count, color)).collect::<Vec<_>>();
+    write!(f, "{}", cubes.join(", "))
 }
 
-fn parse_line(line: &str) -> Result<(u32, Vec<[u32; 3]>)> {
-    let (game_number, sets) = line.split_once(':').ok_or(Error::ParseError)?;
-    let n = game_number.trim().split_ascii_whitespace().last().and_then(|s| s.parse::<u32>().ok()).ok_or(Error::ParseError)?;
-    let mut game = Vec::new();
-    for set in sets.split(';') {
-        let cubes: Result<[u32; 3]> = set.split(',').try_fold([0u32; 3], |mut acc, s| {
-            let (counts, color) = s.trim().split_once(' ').ok_or(Error::ParseError)?;
-            let count = counts.parse::<u32>().map_err(|_| Error::ParseError)?;
-            acc[color_number(color)?] = count;
-            Ok(acc)
-        });
-        game.push(cubes?);
+impl fmt::Display for Draw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        format_cubes(f, &self.0)
     }
-    Ok((n, game))
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
-    let mut acc = 0;
-    for line in lines.iter() {
-        let (n, game) = parse_line(line.as_ref())?;
-        if game.iter().all(|g| is_valid_game(g)) {
-            acc += n;
-        }
+impl FromStr for Draw {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        Draw::new(parse_cubes(s)?)
     }
-    Ok(acc)
 }
 
-pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
-    lines.iter()
-    .map(|s| parse_line(s.as_ref()))
-    .try_fold(0, |acc, res| {
-        match res {
-            Ok((_, game)) => {
-                Ok(acc + game_power(&game))
-            },
-            Err(err) => Err(err)
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let draws = self.draws.iter().map(|draw| draw.to_string()).collect::<Vec<_>>();
+        write!(f, "Game {}: {}", self.id, draws.join("; "))
+    }
+}
+
+impl FromStr for Game {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        let (game_number, sets) = s.split_once(':').ok_or(Error::ParseError)?;
+        let id = game_number.trim().strip_prefix("Game").and_then(|s| s.trim().parse::<u32>().ok()).ok_or(Error::ParseError)?;
+        let draws = sets.split(';').map(|set| set.parse()).collect::<Result<Vec<_>>>()?;
+        Game::new(id, draws)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bag(pub Cubes);
+
+impl Bag {
+    pub fn contains(&self, cubes: &Cubes) -> bool {
+        cubes.iter().all(|(color, count)| *count <= self.0.get(color).copied().unwrap_or(0))
+    }
+
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Result<Self> {
+        let spec = lines.iter().map(|s| s.as_ref().trim()).filter(|s| !s.is_empty()).collect::<Vec<_>>().join(",");
+        spec.parse()
+    }
+}
+
+impl Default for Bag {
+    fn default() -> Self {
+        Bag([("red", 12), ("green", 13), ("blue", 14)].into_iter().map(|(c, n)| (c.to_string(), n)).collect())
+    }
+}
+
+impl fmt::Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        format_cubes(f, &self.0)
+    }
+}
+
+impl FromStr for Bag {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        parse_cubes(s).map(Bag)
+    }
+}
+
+fn is_valid_game(game: &Game, bag: &Bag) -> bool {
+    game.draws.iter().all(|draw| bag.contains(&draw.0))
+}
+
+// product over the bag's colours, a colour missing from the game counts as 0
+fn power(cubes: &Cubes, bag: &Bag) -> u32 {
+    bag.0.keys().map(|color| cubes.get(color).copied().unwrap_or(0)).product()
+}
+
+fn min_cubes(game: &Game) -> Cubes {
+    game.draws.iter().fold(Cubes::new(), |mut acc, draw| {
+        for (color, count) in &draw.0 {
+            let max = acc.entry(color.clone()).or_default();
+            *max = (*max).max(*count);
+        }
+        acc
+    })
+}
+
+fn game_power(game: &Game, bag: &Bag) -> u32 {
+    power(&min_cubes(game), bag)
+}
+
+fn parse_cubes(s: &str) -> Result<Cubes> {
+    s.split(',').try_fold(Cubes::new(), |mut acc, s| {
+        let (counts, color) = s.trim().split_once(' ').ok_or(Error::ParseError)?;
+        let count = counts.parse::<u32>().map_err(|_| Error::ParseError)?;
+        let color = color.trim();
+        if color.is_empty() || color.contains(|c: char| !c.is_alphabetic()) {
+            return Err(Error::ParseError);
         }
+        if acc.insert(color.to_string(), count).is_some() {
+            return Err(Error::DuplicateColor(color.to_string()));
+        }
+        Ok(acc)
     })
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfeasibleDraw {
+    pub game: u32,
+    pub draw: usize,
+    pub excess: Cubes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub games: Vec<Game>,
+}
+
+impl GameRecord {
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Result<Self> {
+        let games = lines.iter().map(|s| s.as_ref().parse()).collect::<Result<Vec<_>>>()?;
+        Ok(Self { games })
+    }
+
+    pub fn minimal_bag(&self) -> Bag {
+        let all = Game { id: 0, draws: self.games.iter().map(|game| Draw(min_cubes(game))).collect() };
+        Bag(min_cubes(&all))
+    }
+
+    pub fn feasible_games(&self, bag: &Bag) -> Vec<u32> {
+        self.games.iter().filter(|game| is_valid_game(game, bag)).map(|game| game.id).collect()
+    }
+
+    pub fn infeasible_draws(&self, bag: &Bag) -> Vec<InfeasibleDraw> {
+        let mut result = Vec::new();
+        for game in &self.games {
+            for (draw, cubes) in game.draws.iter().enumerate() {
+                let excess = cubes.0.iter()
+                    .filter_map(|(color, count)| {
+                        let limit = bag.0.get(color).copied().unwrap_or(0);
+                        (*count > limit).then(|| (color.clone(), count - limit))
+                    })
+                    .collect::<Cubes>();
+                if !excess.is_empty() {
+                    result.push(InfeasibleDraw { game: game.id, draw, excess });
+                }
+            }
+        }
+        result
+    }
+
+    pub fn sole_blocking_draw(&self, id: u32, bag: &Bag) -> Option<usize> {
+        let game = self.games.iter().find(|game| game.id == id)?;
+        let mut blocking = game.draws.iter().enumerate().filter(|(_, draw)| !bag.contains(&draw.0)).map(|(i, _)| i);
+        match (blocking.next(), blocking.next()) {
+            (Some(draw), None) => Some(draw),
+            _ => None,
+        }
+    }
+
+    pub fn power(&self, bag: &Bag) -> u32 {
+        self.games.iter().map(|game| game_power(game, bag)).sum()
+    }
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
+    task1_with(lines, &Bag::default())
+}
+
+pub fn task1_with<S: AsRef<str>>(lines: &[S], bag: &Bag) -> Result<u32> {
+    Ok(GameRecord::from_lines(lines)?.feasible_games(bag).into_iter().sum())
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
+    task2_with(lines, &Bag::default())
+}
+
+pub fn task2_with<S: AsRef<str>>(lines: &[S], bag: &Bag) -> Result<u32> {
+    Ok(GameRecord::from_lines(lines)?.power(bag))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     const DATA: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
     Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
     Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
     Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
     Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
+    fn cubes(s: &str) -> Cubes {
+        parse_cubes(s).unwrap()
+    }
+
+    fn game(draws: &[&str]) -> Game {
+        Game::new(1, draws.iter().map(|s| Draw::new(cubes(s)).unwrap()).collect()).unwrap()
+    }
+
     #[test]
     fn test_example() {
         let lines = DATA.lines().map(|s| s.trim()).collect::<Vec<_>>();
@@ -91,22 +267,94 @@ mod tests {
     fn test_task2() {
         let lines = DATA.lines().map(|s| s.trim()).collect::<Vec<_>>();
         assert_eq!(Ok(2286), task2(&lines));
+        assert_eq!(Ok(0), task2(&["Game 1: 3 red, 2 green"]));
+        assert_eq!(Ok(6), task2_with(&["Game 1: 3 red, 2 green"], &"1 red, 1 green".parse().unwrap()));
     }
 
     #[test]
     fn test_validate() {
-        assert!(is_valid_game(&[12, 13, 14]));
-        assert!(!is_valid_game(&[2, 3, 16]));
+        assert!(is_valid_game(&game(&["12 red, 13 green, 14 blue"]), &Bag::default()));
+        assert!(!is_valid_game(&game(&["2 red, 3 green, 16 blue"]), &Bag::default()));
+        assert!(!is_valid_game(&game(&["1 yellow"]), &Bag::default()));
+        assert!(is_valid_game(&game(&["1 yellow, 2 red"]), &"2 red, 1 yellow".parse().unwrap()));
     }
 
     #[test]
     fn test_power() {
-        assert_eq!(24, power(&[2, 3, 4]));
+        let bag = Bag::default();
+        assert_eq!(24, power(&cubes("2 red, 3 green, 4 blue"), &bag));
+        assert_eq!(24, power(&cubes("2 red, 3 green, 4 blue, 5 yellow"), &bag));
+        assert_eq!(0, power(&cubes("2 red, 4 blue"), &bag));
+        assert_eq!(120, power(&cubes("2 red, 3 green, 4 blue, 5 yellow"), &"1 red, 1 green, 1 blue, 1 yellow".parse().unwrap()));
     }
 
     #[test]
     fn test_game_power() {
-        assert_eq!(30, game_power(&[[1, 1, 2], [5, 1, 2], [1, 3, 1]]));
-        assert_eq!(27, game_power(&[[1, 1, 1], [2, 2, 2], [3, 3, 3]]));
+        let bag = Bag::default();
+        assert_eq!(30, game_power(&game(&["1 red, 1 green, 2 blue", "5 red, 1 green, 2 blue", "1 red, 3 green, 1 blue"]), &bag));
+        assert_eq!(27, game_power(&game(&["1 red, 1 green, 1 blue", "2 red, 2 green, 2 blue", "3 red, 3 green, 3 blue"]), &bag));
+        assert_eq!(0, game_power(&game(&["1 red, 7 yellow", "3 red, 2 blue"]), &bag));
+        assert_eq!(42, game_power(&game(&["1 red, 7 yellow", "3 red, 2 blue"]), &"1 red, 1 yellow, 1 blue".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_record_queries() {
+        let lines = DATA.lines().map(|s| s.trim()).collect::<Vec<_>>();
+        let record = GameRecord::from_lines(&lines).unwrap();
+        assert_eq!(record.minimal_bag().to_string(), "15 blue, 13 green, 20 red");
+        assert_eq!(record.feasible_games(&record.minimal_bag()), vec![1, 2, 3, 4, 5]);
+        assert_eq!(record.feasible_games(&Bag::default()), vec![1, 2, 5]);
+        assert_eq!(record.infeasible_draws(&Bag::default()), vec![
+            InfeasibleDraw { game: 3, draw: 0, excess: cubes("8 red") },
+            InfeasibleDraw { game: 4, draw: 2, excess: cubes("1 blue, 2 red") },
+        ]);
+        assert_eq!(record.sole_blocking_draw(3, &Bag::default()), Some(0));
+        assert_eq!(record.sole_blocking_draw(3, &"19 red, 12 green, 6 blue".parse().unwrap()), None);
+        assert_eq!(record.sole_blocking_draw(1, &Bag::default()), None);
+    }
+
+    #[test]
+    fn test_custom_bag() {
+        let lines = ["Game 1: 3 yellow, 4 red", "Game 2: 5 yellow; 1 red", "Game 3: 1 purple"];
+        assert_eq!(Ok(0), task1(&lines));
+        assert_eq!(Ok(1), task1_with(&lines, &Bag::from_lines(&["4 red", "4 yellow"]).unwrap()));
+        assert_eq!(Ok(0), task2(&lines));
+        assert_eq!(Ok(12 + 5), task2_with(&lines, &Bag::from_lines(&["4 red", "4 yellow"]).unwrap()));
+        assert_eq!(Err(Error::ParseError), "4 red, yellow".parse::<Bag>());
+    }
+
+    #[test]
+    fn test_display() {
+        let game = "Game 7: 4 red, 3 blue; 2 green".parse::<Game>().unwrap();
+        assert_eq!(game.to_string(), "Game 7: 3 blue, 4 red; 2 green");
+        assert_eq!(game.draws()[0].to_string(), "3 blue, 4 red");
+        assert_eq!(Draw::new(Cubes::new()), Err(Error::EmptyDraw));
+        assert_eq!(Game::new(1, Vec::new()), Err(Error::NoDraws(1)));
+        assert_eq!("Game 1: ".parse::<Game>(), Err(Error::ParseError));
+        assert_eq!("Game 1: 3 red;".parse::<Game>(), Err(Error::ParseError));
+        assert_eq!("".parse::<Draw>(), Err(Error::ParseError));
+        assert_eq!("Game 7: 3 red, 4 red".parse::<Game>(), Err(Error::DuplicateColor("red".to_string())));
+        assert_eq!("3 blue, 1 green, 4 blue".parse::<Draw>(), Err(Error::DuplicateColor("blue".to_string())));
+        assert_eq!("Round 7: 3 red".parse::<Game>(), Err(Error::ParseError));
+    }
+
+    fn draw_strategy() -> impl Strategy<Value = Draw> {
+        prop::collection::btree_map("[a-z]{1,8}", 0u32..100, 1..5).prop_map(|cubes| Draw::new(cubes).unwrap())
+    }
+
+    fn game_strategy() -> impl Strategy<Value = Game> {
+        (any::<u32>(), prop::collection::vec(draw_strategy(), 1..6)).prop_map(|(id, draws)| Game::new(id, draws).unwrap())
+    }
+
+    proptest! {
+        #[test]
+        fn draw_round_trip(draw in draw_strategy()) {
+            prop_assert_eq!(draw.to_string().parse::<Draw>(), Ok(draw));
+        }
+
+        #[test]
+        fn game_round_trip(game in game_strategy()) {
+            prop_assert_eq!(game.to_string().parse::<Game>(), Ok(game));
+        }
     }
 }
@@ -0,0 +1,117 @@
+use crate::schematic::{normalise, Schematic};
+use crate::task::{GearRule, Task1Checker, Task2Checker};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Plain,
+    Part,
+    NonPart,
+    Symbol,
+    Gear,
+}
+
+impl Cell {
+    fn ansi(&self) -> Option<&'static str> {
+        match self {
+            Cell::Plain => None,
+            Cell::Part => Some("\x1b[32m"),
+            Cell::NonPart => Some("\x1b[31m"),
+            Cell::Symbol => Some("\x1b[36m"),
+            Cell::Gear => Some("\x1b[1;33m"),
+        }
+    }
+
+    fn class(&self) -> Option<&'static str> {
+        match self {
+            Cell::Plain => None,
+            Cell::Part => Some("part"),
+            Cell::NonPart => Some("non-part"),
+            Cell::Symbol => Some("symbol"),
+            Cell::Gear => Some("gear"),
+        }
+    }
+}
+
+pub fn classify<S: AsRef<str>>(lines: &[S], schematic: &Schematic, rule: &GearRule) -> Vec<Vec<Cell>> {
+    let mut cells = lines.iter().map(|s| vec![Cell::Plain; normalise(s.as_ref()).len()]).collect::<Vec<_>>();
+    for (id, number) in schematic.numbers().iter().enumerate() {
+        let cell = if Task1Checker.is_part(schematic, id) { Cell::Part } else { Cell::NonPart };
+        cells[number.row][number.col_span.clone()].fill(cell);
+    }
+    let checker = Task2Checker { rule };
+    for (id, symbol) in schematic.symbols().iter().enumerate() {
+        cells[symbol.pos.0][symbol.pos.1] = if checker.is_gear(schematic, id) { Cell::Gear } else { Cell::Symbol };
+    }
+    cells
+}
+
+// consecutive characters sharing a classification
+fn runs<'a>(line: &'a str, cells: &'a [Cell]) -> impl Iterator<Item = (Cell, &'a str)> + 'a {
+    let mut start = 0;
+    (1..=line.len()).filter_map(move |end| {
+        if end < line.len() && cells[end] == cells[start] {
+            return None;
+        }
+        let run = (cells[start], &line[start..end]);
+        start = end;
+        Some(run)
+    })
+}
+
+pub fn render_ansi<S: AsRef<str>>(lines: &[S], cells: &[Vec<Cell>]) -> String {
+    let mut out = String::new();
+    for (line, cells) in lines.iter().zip(cells) {
+        for (cell, text) in runs(normalise(line.as_ref()), cells) {
+            match cell.ansi() {
+                Some(code) => out.push_str(&format!("{}{}\x1b[0m", code, text)),
+                None => out.push_str(text),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+pub fn render_html<S: AsRef<str>>(lines: &[S], cells: &[Vec<Cell>]) -> String {
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>day03 schematic</title>\n<style>\n",
+        "body { background: #111; color: #888; }\n",
+        ".part { color: #3c3; }\n.non-part { color: #e33; }\n.symbol { color: #3cc; }\n.gear { color: #fd3; font-weight: bold; }\n",
+        "</style>\n</head>\n<body>\n<pre>\n",
+    ));
+    for (line, cells) in lines.iter().zip(cells) {
+        for (cell, text) in runs(normalise(line.as_ref()), cells) {
+            match cell.class() {
+                Some(class) => out.push_str(&format!("<span class=\"{}\">{}</span>", class, escape(text))),
+                None => out.push_str(&escape(text)),
+            }
+        }
+        out.push('\n');
+    }
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let lines = ["467..114..", "...*......", "..35..<33."];
+        let schematic = Schematic::parse(&lines).unwrap();
+        let cells = classify(&lines, &schematic, &GearRule::default());
+        assert_eq!(cells[0][..6], [Cell::Part, Cell::Part, Cell::Part, Cell::Plain, Cell::Plain, Cell::NonPart]);
+        assert_eq!(cells[1][3], Cell::Gear);
+        assert_eq!(cells[2][6..8], [Cell::Symbol, Cell::Part]);
+        let ansi = render_ansi(&lines, &cells);
+        assert!(ansi.starts_with("\x1b[32m467\x1b[0m..\x1b[31m114\x1b[0m..\n"));
+        let html = render_html(&lines, &cells);
+        assert!(html.contains("<span class=\"symbol\">&lt;</span><span class=\"part\">33</span>."));
+        assert!(html.contains("...<span class=\"gear\">*</span>......\n"));
+    }
+}
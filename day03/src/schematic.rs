@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use crate::task::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartNumber {
+    pub value: u32,
+    pub row: usize,
+    pub col_span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub ch: char,
+    pub pos: (usize, usize),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Schematic {
+    numbers: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+    // symbol ids adjacent to each number, number ids adjacent to each symbol
+    number_edges: Vec<Vec<usize>>,
+    symbol_edges: Vec<Vec<usize>>,
+}
+
+pub(crate) fn normalise(line: &str) -> &str {
+    line.trim_end_matches(['\r', '\n'])
+}
+
+fn is_blank(b: u8) -> bool {
+    b == b'.' || b.is_ascii_whitespace()
+}
+
+fn check_around(grid: &[&[u8]], symbols: &HashMap<(usize, usize), usize>, row: usize, cols: &Range<usize>) -> Vec<usize> {
+    let mut found = Vec::new();
+    for (i, line) in grid.iter().enumerate().take(row + 2).skip(row.saturating_sub(1)) {
+        for j in cols.start.saturating_sub(1)..(cols.end + 1).min(line.len()) {
+            if let Some(id) = symbols.get(&(i, j)) {
+                found.push(*id);
+            }
+        }
+    }
+    found
+}
+
+impl Schematic {
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Self> {
+        let grid = lines.iter().map(|s| normalise(s.as_ref()).as_bytes()).collect::<Vec<_>>();
+        let mut schematic = Schematic::default();
+        let mut positions = HashMap::new();
+        for (i, row) in grid.iter().enumerate() {
+            for (j, b) in row.iter().enumerate() {
+                if !b.is_ascii() {
+                    return Err(Error::NonAscii(i + 1, j + 1));
+                }
+                if !b.is_ascii_digit() && !is_blank(*b) {
+                    positions.insert((i, j), schematic.symbols.len());
+                    schematic.symbols.push(Symbol { ch: *b as char, pos: (i, j) });
+                }
+            }
+        }
+        schematic.symbol_edges = vec![Vec::new(); schematic.symbols.len()];
+        for (i, row) in grid.iter().enumerate() {
+            let m = row.len();
+            let mut j = 0;
+            while j < m {
+                if row[j].is_ascii_digit() {
+                    let mut jlast = j;
+                    while jlast < m && row[jlast].is_ascii_digit() {
+                        jlast += 1;
+                    }
+                    let value = std::str::from_utf8(&row[j..jlast])?.parse::<u32>()?;
+                    let id = schematic.numbers.len();
+                    let around = check_around(&grid, &positions, i, &(j..jlast));
+                    for symbol in &around {
+                        schematic.symbol_edges[*symbol].push(id);
+                    }
+                    schematic.numbers.push(PartNumber { value, row: i, col_span: j..jlast });
+                    schematic.number_edges.push(around);
+                    j = jlast;
+                } else {
+                    j += 1;
+                }
+            }
+        }
+        Ok(schematic)
+    }
+
+    pub fn numbers(&self) -> &[PartNumber] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn number_neighbours(&self, id: usize) -> impl Iterator<Item = &Symbol> + '_ {
+        self.number_edges[id].iter().map(|s| &self.symbols[*s])
+    }
+
+    pub fn symbol_neighbours(&self, id: usize) -> impl Iterator<Item = &PartNumber> + '_ {
+        self.symbol_edges[id].iter().map(|n| &self.numbers[*n])
+    }
+
+    pub fn numbers_touching(&self, ch: char) -> impl Iterator<Item = &PartNumber> + '_ {
+        (0..self.numbers.len()).filter(move |id| self.number_neighbours(*id).any(|s| s.ch == ch)).map(|id| &self.numbers[id])
+    }
+
+    pub fn symbols_with_neighbours(&self, k: usize) -> impl Iterator<Item = &Symbol> + '_ {
+        (0..self.symbols.len()).filter(move |id| self.symbol_edges[*id].len() == k).map(|id| &self.symbols[id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const DATA: &str =
+"467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn test_graph() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let schematic = Schematic::parse(&lines).unwrap();
+        assert_eq!(schematic.numbers().len(), 10);
+        assert_eq!(schematic.symbols().len(), 6);
+        assert_eq!(schematic.numbers()[0], PartNumber { value: 467, row: 0, col_span: 0..3 });
+        assert_eq!(schematic.numbers_touching('#').map(|n| n.value).collect::<Vec<_>>(), vec![633]);
+        assert_eq!(schematic.numbers_touching('*').map(|n| n.value).collect::<Vec<_>>(), vec![467, 35, 617, 755, 598]);
+        let pairs = schematic.symbols_with_neighbours(2).map(|s| (s.ch, s.pos)).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('*', (1, 3)), ('*', (8, 5))]);
+        assert_eq!(schematic.symbols_with_neighbours(0).count(), 0);
+        assert_eq!(schematic.number_neighbours(1).count(), 0);
+        assert_eq!(schematic.symbol_neighbours(0).map(|n| n.value).collect::<Vec<_>>(), vec![467, 35]);
+    }
+
+    #[test]
+    fn test_ragged() {
+        let lines = ["467..114\r", "...*", "..35..633..\r", "......#"];
+        let schematic = Schematic::parse(&lines).unwrap();
+        assert_eq!(schematic.symbols().iter().map(|s| (s.ch, s.pos)).collect::<Vec<_>>(), vec![('*', (1, 3)), ('#', (3, 6))]);
+        assert_eq!(schematic.numbers_touching('*').map(|n| n.value).collect::<Vec<_>>(), vec![467, 35]);
+        assert_eq!(schematic.numbers_touching('#').map(|n| n.value).collect::<Vec<_>>(), vec![633]);
+        assert_eq!(schematic.numbers()[1].col_span, 5..8);
+        let lines = ["...", "1.", "", "    ", "2*"];
+        let schematic = Schematic::parse(&lines).unwrap();
+        assert_eq!(schematic.symbols().len(), 1);
+        assert_eq!(schematic.numbers_touching('*').map(|n| n.value).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        let lines = ["467..", "..é.."];
+        assert_eq!(Schematic::parse(&lines).unwrap_err(), Error::NonAscii(2, 3));
+    }
+
+    #[test]
+    fn test_empty() {
+        let schematic = Schematic::parse::<&str>(&[]).unwrap();
+        assert!(schematic.numbers().is_empty());
+    }
+}
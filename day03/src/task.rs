@@ -1,4 +1,5 @@
-use std::{str::Utf8Error, num::ParseIntError, collections::{HashMap, HashSet}};
+use std::{str::{FromStr, Utf8Error}, num::ParseIntError};
+use crate::schematic::Schematic;
 
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
@@ -6,129 +7,121 @@ pub enum Error {
     ParseUtf8Error(#[from] Utf8Error),
     #[error("Parse int error")]
     ParseIntError(#[from] ParseIntError),
+    #[error("Non-ASCII byte at line {0}, column {1}")]
+    NonAscii(usize, usize),
+    #[error("Invalid gear rule {0}")]
+    InvalidRule(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 trait Checker {
-    fn check_around(&mut self, ii: usize, jj: usize);
-    fn apply(&mut self, value: u32);
-    fn result(&self) -> u32;
+    fn result(&self, schematic: &Schematic) -> u32;
 }
 
-struct Task1Checker<'a> {
-    grid: &'a [&'a [u8]],
-    acc: u32,
-    found: bool,
-}
+pub(crate) struct Task1Checker;
 
-impl<'a> Task1Checker<'a> {
-    fn new(grid: &'a [&'a [u8]]) -> Self {
-        Self { grid, acc: 0, found: false }
+impl Task1Checker {
+    pub(crate) fn is_part(&self, schematic: &Schematic, id: usize) -> bool {
+        schematic.number_neighbours(id).next().is_some()
     }
-    fn is_symbol(&self, i: usize, j: usize) -> bool {
-        !self.grid[i][j].is_ascii_digit() && self.grid[i][j] != b'.'
+}
+
+impl Checker for Task1Checker {
+    fn result(&self, schematic: &Schematic) -> u32 {
+        (0..schematic.numbers().len()).filter(|id| self.is_part(schematic, *id)).map(|id| schematic.numbers()[id].value).sum()
     }
 }
 
-impl<'a> Checker for Task1Checker<'a> {
-    fn check_around(&mut self, ii: usize, jj: usize) {
-        let n = self.grid.len();
-        let m = self.grid[0].len();
-        for i in (ii.checked_sub(1).unwrap_or(0))..(ii + 2).min(n) {
-            for j in (jj.checked_sub(1).unwrap_or(0))..(jj + 2).min(m) {
-                if i == ii && j == jj {
-                    continue;
-                }
-                if self.is_symbol(i, j) {
-                    self.found = true;
-                    return;
-                }
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Reduction {
+    fn reduce<I: Iterator<Item = u32>>(&self, values: I) -> u32 {
+        match self {
+            Reduction::Product => values.product(),
+            Reduction::Sum => values.sum(),
+            Reduction::Max => values.max().unwrap_or(0),
         }
     }
-    fn apply(&mut self, value: u32) {
-        if self.found {
-            self.acc += value;
+}
+
+impl FromStr for Reduction {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Reduction::Product),
+            "sum" => Ok(Reduction::Sum),
+            "max" => Ok(Reduction::Max),
+            _ => Err(Error::InvalidRule(s.to_string())),
         }
-        self.found = false;
-    }
-    fn result(&self) -> u32 {
-        self.acc
     }
 }
 
-
-struct Task2Checker<'a> {
-    grid: &'a [&'a [u8]],
-    all_stars: HashMap<(usize, usize), Vec<u32>>,
-    tmp_stars: HashSet<(usize, usize)>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbols: Vec<char>,
+    pub neighbours: usize,
+    pub reduction: Reduction,
 }
 
-impl<'a> Task2Checker<'a> {
-    fn new(grid: &'a [&'a [u8]]) -> Self {
-        Self { grid, all_stars: HashMap::new(), tmp_stars: HashSet::new()}
-    }
-    fn is_symbol(&self, i: usize, j: usize) -> bool {
-        self.grid[i][j] == b'*'
+impl Default for GearRule {
+    fn default() -> Self {
+        Self { symbols: vec!['*'], neighbours: 2, reduction: Reduction::Product }
     }
 }
 
-impl<'a> Checker for Task2Checker<'a> {
-    fn check_around(&mut self, ii: usize, jj: usize) {
-        let n = self.grid.len();
-        let m = self.grid[0].len();
-        for i in (ii.checked_sub(1).unwrap_or(0))..(ii + 2).min(n) {
-            for j in (jj.checked_sub(1).unwrap_or(0))..(jj + 2).min(m) {
-                if i == ii && j == jj {
-                    continue;
-                }
-                if self.is_symbol(i, j) {
-                    self.tmp_stars.insert((i, j));
-                }
-            }
+// "<symbols> <neighbours> <reduction>", e.g. "*# 3 sum"
+impl FromStr for GearRule {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        match s.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+            [symbols, neighbours, reduction] => Ok(GearRule {
+                symbols: symbols.chars().collect(),
+                neighbours: neighbours.parse().map_err(|_| Error::InvalidRule(s.to_string()))?,
+                reduction: reduction.parse()?,
+            }),
+            _ => Err(Error::InvalidRule(s.to_string())),
         }
     }
-    fn apply(&mut self, value: u32) {
-        for star in &self.tmp_stars {
-            self.all_stars.entry(*star).or_default().push(value);
-        }
-        self.tmp_stars.clear();
-    }
-    fn result(&self) -> u32 {
-        self.all_stars.values().filter(|v| v.len() == 2).map(|v| v[0] * v[1]).sum()
+}
+
+pub(crate) struct Task2Checker<'a> {
+    pub(crate) rule: &'a GearRule,
+}
+
+impl Task2Checker<'_> {
+    pub(crate) fn is_gear(&self, schematic: &Schematic, id: usize) -> bool {
+        self.rule.symbols.contains(&schematic.symbols()[id].ch) && schematic.symbol_neighbours(id).count() == self.rule.neighbours
     }
 }
 
-fn process<C: Checker>(grid: &[&[u8]], mut checker: C) -> Result<u32> {
-    let (n, m) = (grid.len(), grid[0].len());
-    for i in 0..n {
-        let mut j = 0;
-        while j < m {
-            if grid[i][j].is_ascii_digit() {
-                let mut jlast= j;
-                while jlast < m && grid[i][jlast].is_ascii_digit() {
-                    checker.check_around(i, jlast);
-                    jlast += 1;
-                }
-                checker.apply(std::str::from_utf8(&grid[i][j..jlast])?.parse::<u32>()?);
-                j = jlast;
-            } else {
-                j += 1;
-            }
-        }
+impl Checker for Task2Checker<'_> {
+    fn result(&self, schematic: &Schematic) -> u32 {
+        (0..schematic.symbols().len()).filter(|id| self.is_gear(schematic, *id))
+            .map(|id| self.rule.reduction.reduce(schematic.symbol_neighbours(id).map(|n| n.value)))
+            .sum()
     }
-    Ok(checker.result())
+}
+
+fn process<S: AsRef<str>, C: Checker>(lines: &[S], checker: C) -> Result<u32> {
+    Ok(checker.result(&Schematic::parse(lines)?))
 }
 
 pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
-    let grid = lines.into_iter().map(|s| s.as_ref().as_bytes()).collect::<Vec<_>>();
-    process(&grid, Task1Checker::new(&grid))
+    process(lines, Task1Checker)
 }
 
 pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
-    let grid = lines.into_iter().map(|s| s.as_ref().as_bytes()).collect::<Vec<_>>();
-    process(&grid, Task2Checker::new(&grid))
+    task2_with(lines, &GearRule::default())
+}
+
+pub fn task2_with<S: AsRef<str>>(lines: &[S], rule: &GearRule) -> Result<u32> {
+    process(lines, Task2Checker { rule })
 }
 
 #[cfg(test)]
@@ -158,4 +151,15 @@ mod tests {
         assert_eq!(Ok(467835), task2(&lines));
     }
 
+    #[test]
+    fn test_gear_rules() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        assert_eq!(Ok(467 + 35 + 755 + 598), task2_with(&lines, &"* 2 sum".parse().unwrap()));
+        assert_eq!(Ok(617), task2_with(&lines, &GearRule { neighbours: 1, reduction: Reduction::Max, ..GearRule::default() }));
+        assert_eq!(Ok(617 + 633 + 592), task2_with(&lines, &"*#+ 1 max".parse().unwrap()));
+        assert_eq!(Ok(664), task2_with(&lines, &"$ 1 product".parse().unwrap()));
+        assert_eq!("* two sum".parse::<GearRule>(), Err(Error::InvalidRule("* two sum".to_string())));
+        assert_eq!("* 2 min".parse::<GearRule>(), Err(Error::InvalidRule("min".to_string())));
+    }
+
 }
@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use day04::task::{task1, Card};
+
+// the HashSet representation the bitset replaced
+struct HashCard {
+    win: HashSet<u32>,
+    got: Vec<u32>,
+}
+
+impl HashCard {
+    fn parse(s: &str) -> Self {
+        let (_, nums) = s.split_once(':').unwrap();
+        let (win, got) = nums.split_once('|').unwrap();
+        let win = win.split_ascii_whitespace().map(|n| n.parse().unwrap()).collect();
+        let got = got.split_ascii_whitespace().map(|n| n.parse().unwrap()).collect();
+        Self { win, got }
+    }
+
+    fn count(&self) -> usize {
+        self.got.iter().filter(|x| self.win.contains(x)).count()
+    }
+}
+
+fn deck(cards: usize, max: u64) -> Vec<String> {
+    let mut seed = 0x2545f4914f6cdd1du64;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+    (1..=cards).map(|id| {
+        let win = (0..10).map(|_| (next() % max + 1).to_string()).collect::<Vec<_>>();
+        let got = (0..25).map(|_| (next() % max + 1).to_string()).collect::<Vec<_>>();
+        format!("Card {}: {} | {}", id, win.join(" "), got.join(" "))
+    }).collect()
+}
+
+fn bench(c: &mut Criterion) {
+    for (name, max) in [("small", 99), ("large", 999)] {
+        let lines = deck(100_000, max);
+        let cards = lines.iter().map(|s| s.parse::<Card>().unwrap()).collect::<Vec<_>>();
+        let hash_cards = lines.iter().map(|s| HashCard::parse(s)).collect::<Vec<_>>();
+        let mut group = c.benchmark_group(format!("cards_{}", name));
+        group.throughput(Throughput::Elements(lines.len() as u64));
+        group.bench_function("count_bitset", |b| b.iter(|| cards.iter().map(Card::count).sum::<usize>()));
+        group.bench_function("count_hashset", |b| b.iter(|| hash_cards.iter().map(HashCard::count).sum::<usize>()));
+        group.bench_function("task1_bitset", |b| b.iter(|| task1(&lines)));
+        group.bench_function("task1_hashset", |b| b.iter(|| lines.iter().map(|s| HashCard::parse(s).count()).sum::<usize>()));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
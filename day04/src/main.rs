@@ -1,32 +1,99 @@
 mod input;
-mod task;
 
 use input::{read_file, read_stdin};
-use std::io;
+use common::profile::{measure, report};
+use common::store::{read_store, record_store};
+use std::fs::File;
+use std::io::{self, BufRead};
 use std::env;
-use task::*;
+use day04::task::*;
 
 enum Mode {
     File(String),
-    Stdin
+    Stdin,
+    Store(Option<String>),
+}
+
+fn stream<R: BufRead>(reader: R, duplicates: Duplicates) -> io::Result<()> {
+    let mut read_error = None;
+    let lines = reader.lines().map_while(|line| line.map_err(|error| read_error = Some(error)).ok());
+    let result = task2_stream(lines, duplicates);
+    if let Some(error) = read_error {
+        return Err(error);
+    }
+    println!("result2 {}", result.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?);
+    Ok(())
+}
+
+fn take_flag<T: std::str::FromStr<Err = Error> + Default>(args: &mut Vec<String>, flag: &str) -> io::Result<T> {
+    match args.iter().position(|arg| arg == flag) {
+        Some(i) if i + 1 < args.len() => {
+            let value = args.drain(i..i + 2).nth(1).unwrap();
+            value.parse().map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, format!("{} {}: {}", flag, value, error)))
+        },
+        _ => Ok(T::default()),
+    }
 }
 
 fn main() -> io::Result<()> {
-    let mode = env::args().nth(1).map(|arg| Mode::File(arg)).unwrap_or(Mode::Stdin);
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let scoring = take_flag::<ScoringRule>(&mut args, "--scoring")?;
+    let reward = take_flag::<RewardRule>(&mut args, "--reward")?;
+    let duplicates = take_flag::<Duplicates>(&mut args, "--duplicates")?;
+    let check = args.first().is_some_and(|arg| arg == "validate");
+    if check {
+        args.remove(0);
+    }
+    let mut args = args.into_iter();
+    let mode = match args.next() {
+        Some(arg) if arg == "--stream" && reward != RewardRule::Next => {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "--stream only supports the next reward rule"));
+        },
+        Some(arg) if arg == "--stream" => return match args.next() {
+            Some(path) if path != "-" => stream(io::BufReader::new(File::open(path)?), duplicates),
+            _ => stream(io::stdin().lock(), duplicates),
+        },
+        Some(arg) if arg == "--profile" => match args.next() {
+            Some(profile) => Mode::Store(Some(profile)),
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "--profile needs a name")),
+        },
+        Some(arg) if arg == "--record" => return record_store(env!("CARGO_PKG_NAME"), args.next().as_deref()),
+        Some(arg) if arg == "-" => Mode::Stdin,
+        Some(arg) => Mode::File(arg),
+        None => Mode::Store(None),
+    };
     let data = match mode {
         Mode::File(file_path) => read_file(&file_path)?,
-        Mode::Stdin => read_stdin()?
+        Mode::Stdin => read_stdin()?,
+        Mode::Store(profile) => match read_store(env!("CARGO_PKG_NAME"), profile.as_deref())? {
+            Some(data) => data,
+            None if profile.is_none() => read_stdin()?,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "stored input not found")),
+        },
     };
 
-    match task1(&data) {
+    if check {
+        let issues = validate(&data);
+        for (line, issue) in &issues {
+            println!("line {}: {}", line, issue);
+        }
+        println!("{} issues", issues.len());
+        return Ok(());
+    }
+
+    let (result, usage) = measure(|| task1_with(&data, &scoring, duplicates));
+    match result {
         Ok(result) => println!("result1 {}", result),
         Err(error) => println!("error {}", error)
     }
+    report(env!("CARGO_PKG_NAME"), "task1", usage);
 
-    match task2(&data) {
+    let (result, usage) = measure(|| task2_with(&data, reward, duplicates));
+    match result {
         Ok(result) => println!("result2 {}", result),
         Err(error) => println!("error {}", error)
     }
+    report(env!("CARGO_PKG_NAME"), "task2", usage);
 
     Ok(())
 }
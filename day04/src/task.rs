@@ -1,5 +1,6 @@
 use std::str::FromStr;
 use std::collections::HashSet;
+use std::fmt;
 use std::num::ParseIntError;
 
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
@@ -8,28 +9,147 @@ pub enum Error {
     ParseIntError(#[from] ParseIntError),
     #[error("Format error")]
     FormatError,
+    #[error("Invalid rule {0}")]
+    InvalidRule(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-struct Card {
-    #[allow(dead_code)]
+// bitset over 0..128 with a sorted fallback for larger numbers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberSet {
+    bits: u128,
+    large: Vec<u32>,
+}
+
+impl NumberSet {
+    pub fn insert(&mut self, n: u32) -> bool {
+        if n < u128::BITS {
+            let added = self.bits & (1 << n) == 0;
+            self.bits |= 1 << n;
+            return added;
+        }
+        match self.large.binary_search(&n) {
+            Ok(_) => false,
+            Err(i) => {
+                self.large.insert(i, n);
+                true
+            }
+        }
+    }
+
+    pub fn contains(&self, n: u32) -> bool {
+        if n < u128::BITS {
+            return self.bits & (1 << n) != 0;
+        }
+        self.large.binary_search(&n).is_ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize + self.large.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn intersection_count(&self, other: &NumberSet) -> usize {
+        let (mut i, mut j, mut count) = (0, 0, (self.bits & other.bits).count_ones() as usize);
+        while i < self.large.len() && j < other.large.len() {
+            match self.large[i].cmp(&other.large[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    count += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+impl FromIterator<u32> for NumberSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut set = NumberSet::default();
+        for n in iter {
+            if n < u128::BITS {
+                set.bits |= 1 << n;
+            } else {
+                set.large.push(n);
+            }
+        }
+        set.large.sort_unstable();
+        set.large.dedup();
+        set
+    }
+}
+
+impl FromStr for NumberSet {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        s.split_ascii_whitespace().map(|n| n.parse::<u32>().map_err(Error::from)).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Duplicates {
+    Once,
+    #[default]
+    Multiple,
+}
+
+impl FromStr for Duplicates {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        match s {
+            "once" => Ok(Duplicates::Once),
+            "multiple" => Ok(Duplicates::Multiple),
+            _ => Err(Error::InvalidRule(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
     id: u32,
-    win: HashSet<u32>,
-    got: Vec<u32>,
+    win: NumberSet,
+    got: NumberSet,
+    // occurrences beyond the first of repeated numbers
+    win_repeats: Vec<u32>,
+    got_repeats: Vec<u32>,
 }
 
-impl Card {
-    fn score(&self) -> u32 {
-        let count = self.got.iter().filter(|x| self.win.contains(x)).count() as u32;
-        if count == 0 {
-            return 0;
+fn parse_numbers(s: &str) -> Result<(NumberSet, Vec<u32>)> {
+    let mut set = NumberSet::default();
+    let mut repeats = Vec::new();
+    for n in s.split_ascii_whitespace() {
+        let n = n.parse::<u32>()?;
+        if !set.insert(n) {
+            repeats.push(n);
         }
-        return 1 << (count - 1);
     }
-    fn count(&self) -> usize {
-        let count = self.got.iter().filter(|x| self.win.contains(x)).count();
-        return count;
+    Ok((set, repeats))
+}
+
+impl Card {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn score(&self) -> u32 {
+        ScoringRule::Doubling.score(self.count())
+    }
+    pub fn count(&self) -> usize {
+        self.count_with(Duplicates::default())
+    }
+    // with Multiple every held occurrence of a winning number is a match
+    pub fn count_with(&self, duplicates: Duplicates) -> usize {
+        let count = self.win.intersection_count(&self.got);
+        match duplicates {
+            Duplicates::Once => count,
+            Duplicates::Multiple => count + self.got_repeats.iter().filter(|n| self.win.contains(**n)).count(),
+        }
     }
 }
 
@@ -39,26 +159,202 @@ impl FromStr for Card {
         let (card_str, nums) = s.split_once(':').ok_or(Error::FormatError)?;
         let id = card_str.split_ascii_whitespace().nth(1).ok_or(Error::FormatError)?.parse::<u32>()?;
         let (win_str, got_str) = nums.split_once('|').ok_or(Error::FormatError)?;
-        let win = win_str.trim().split_ascii_whitespace().map(|n| n.parse::<u32>()).collect::<std::result::Result<HashSet<u32>, ParseIntError>>()?;
-        let got = got_str.trim().split_ascii_whitespace().map(|n| n.parse::<u32>()).collect::<std::result::Result<Vec<u32>, ParseIntError>>()?;
-        Ok(Card { id, win, got })
+        let (win, win_repeats) = parse_numbers(win_str)?;
+        let (got, got_repeats) = parse_numbers(got_str)?;
+        Ok(Card { id, win, got, win_repeats, got_repeats })
     }
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
-    let cards = lines.iter().map(|s| s.as_ref().parse::<Card>()).collect::<Result<Vec<Card>>>()?;
-    Ok(cards.into_iter().map(|c| c.score()).sum())
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ScoringRule {
+    #[default]
+    Doubling,
+    Linear,
+    Fibonacci,
+    // points by match count, the last entry repeating for higher counts
+    Table(Vec<u32>),
+}
+
+impl ScoringRule {
+    pub fn score(&self, matches: usize) -> u32 {
+        match self {
+            _ if matches == 0 => 0,
+            ScoringRule::Doubling => 1u32.checked_shl(matches as u32 - 1).unwrap_or(u32::MAX),
+            ScoringRule::Linear => matches as u32,
+            ScoringRule::Fibonacci => (1..matches).fold((1u32, 1u32), |(a, b), _| (b, a.saturating_add(b))).0,
+            ScoringRule::Table(table) => table.get(matches).or(table.last()).copied().unwrap_or(0),
+        }
+    }
+}
+
+// "doubling", "linear", "fibonacci" or "table:0,1,3,7"
+impl FromStr for ScoringRule {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "doubling" => Ok(ScoringRule::Doubling),
+            None if s == "linear" => Ok(ScoringRule::Linear),
+            None if s == "fibonacci" => Ok(ScoringRule::Fibonacci),
+            Some(("table", table)) => table.split(',').map(|n| n.trim().parse::<u32>().map_err(|_| Error::InvalidRule(s.to_string())))
+                .collect::<Result<Vec<_>>>().map(ScoringRule::Table),
+            _ => Err(Error::InvalidRule(s.to_string())),
+        }
+    }
 }
 
-pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RewardRule {
+    // copies of the next N cards
+    #[default]
+    Next,
+    // copies of the previous N cards, cascading from the back of the deck
+    Previous,
+    // copies of the next N cards modulo the deck length; copies landing on an
+    // already scored card are counted but do not cascade again
+    WrapAround,
+}
+
+impl RewardRule {
+    pub fn cascade(&self, matches: &[usize]) -> u64 {
+        let n = matches.len();
+        let mut counts = vec![1u64; n];
+        for step in 0..n {
+            let i = if *self == RewardRule::Previous { n - 1 - step } else { step };
+            let copies = counts[i];
+            for k in 1..=matches[i] {
+                let j = match self {
+                    RewardRule::Next if i + k < n => i + k,
+                    RewardRule::Previous if k <= i => i - k,
+                    RewardRule::WrapAround => (i + k) % n,
+                    _ => break,
+                };
+                counts[j] += copies;
+            }
+        }
+        counts.into_iter().sum()
+    }
+}
+
+impl FromStr for RewardRule {
+    type Err = Error;
+    fn from_str(s: &str) -> std::prelude::v1::Result<Self, Self::Err> {
+        match s {
+            "next" => Ok(RewardRule::Next),
+            "previous" => Ok(RewardRule::Previous),
+            "wrap" => Ok(RewardRule::WrapAround),
+            _ => Err(Error::InvalidRule(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    NonSequentialId { expected: u32, found: u32 },
+    DuplicateId(u32),
+    DuplicateWinning(u32),
+    DuplicateHeld(u32),
+    Unparsable(Error),
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::NonSequentialId { expected, found } => write!(f, "expected card {} but found card {}", expected, found),
+            Issue::DuplicateId(id) => write!(f, "duplicate card id {}", id),
+            Issue::DuplicateWinning(n) => write!(f, "duplicate winning number {}", n),
+            Issue::DuplicateHeld(n) => write!(f, "duplicate held number {}", n),
+            Issue::Unparsable(error) => write!(f, "unparsable card: {}", error),
+        }
+    }
+}
+
+// (1-based line number, issue) for every problem in the deck
+pub fn validate<S: AsRef<str>>(lines: &[S]) -> Vec<(usize, Issue)> {
+    let mut issues = Vec::new();
+    let mut seen = HashSet::new();
+    let mut expected = 1;
+    for (i, line) in lines.iter().enumerate() {
+        // an unparsable line is taken to be the expected card
+        let card = match line.as_ref().parse::<Card>() {
+            Ok(card) => card,
+            Err(error) => {
+                issues.push((i + 1, Issue::Unparsable(error)));
+                expected += 1;
+                continue;
+            },
+        };
+        if !seen.insert(card.id) {
+            issues.push((i + 1, Issue::DuplicateId(card.id)));
+        } else if card.id != expected {
+            issues.push((i + 1, Issue::NonSequentialId { expected, found: card.id }));
+        }
+        expected = card.id + 1;
+        issues.extend(card.win_repeats.iter().map(|n| (i + 1, Issue::DuplicateWinning(*n))));
+        issues.extend(card.got_repeats.iter().map(|n| (i + 1, Issue::DuplicateHeld(*n))));
+    }
+    issues
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
+    task1_with(lines, &ScoringRule::default(), Duplicates::default())
+}
+
+// scores are capped at u32::MAX, so the total is summed in u64 and saturates
+pub fn task1_with<S: AsRef<str>>(lines: &[S], rule: &ScoringRule, duplicates: Duplicates) -> Result<u64> {
     let cards = lines.iter().map(|s| s.as_ref().parse::<Card>()).collect::<Result<Vec<Card>>>()?;
-    let mut counts = vec![1; cards.len()];
-    for i in 0..cards.len() {
-        let win_count = cards[i].count();
-        let n = counts[i];
-        counts.iter_mut().skip(i + 1).take(win_count).for_each(|x| *x += n);
+    Ok(cards.into_iter().map(|c| rule.score(c.count_with(duplicates)) as u64).fold(0, u64::saturating_add))
+}
+
+// pending extra copies of the upcoming cards, indexed from head
+#[derive(Debug, Clone, Default)]
+pub struct Cascade {
+    ring: Vec<u64>,
+    head: usize,
+    total: u64,
+}
+
+impl Cascade {
+    pub fn push(&mut self, matches: usize) -> u64 {
+        let copies = 1 + self.ring.get_mut(self.head).map_or(0, std::mem::take);
+        if matches > self.ring.len() {
+            self.ring.rotate_left(self.head);
+            self.head = 0;
+            self.ring.resize(matches, 0);
+        }
+        let len = self.ring.len();
+        if len > 0 {
+            self.head = (self.head + 1) % len;
+        }
+        for k in 0..matches {
+            self.ring[(self.head + k) % len] += copies;
+        }
+        self.total += copies;
+        copies
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
     }
-    Ok(counts.into_iter().sum())
+}
+
+pub fn task2_stream<I: IntoIterator<Item = S>, S: AsRef<str>>(lines: I, duplicates: Duplicates) -> Result<u64> {
+    let mut cascade = Cascade::default();
+    for line in lines {
+        cascade.push(line.as_ref().parse::<Card>()?.count_with(duplicates));
+    }
+    Ok(cascade.total())
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
+    task2_stream(lines, Duplicates::default())
+}
+
+pub fn task2_with<S: AsRef<str>>(lines: &[S], rule: RewardRule, duplicates: Duplicates) -> Result<u64> {
+    if rule == RewardRule::Next {
+        return task2_stream(lines, duplicates);
+    }
+    let matches = lines.iter().map(|s| s.as_ref().parse::<Card>().map(|c| c.count_with(duplicates))).collect::<Result<Vec<_>>>()?;
+    Ok(rule.cascade(&matches))
 }
 
 #[cfg(test)]
@@ -84,4 +380,108 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         assert_eq!(Ok(30), task2(&lines));
     }
 
+    fn naive_cascade(matches: &[usize]) -> u64 {
+        let mut counts = vec![1; matches.len()];
+        for i in 0..matches.len() {
+            let n = counts[i];
+            counts.iter_mut().skip(i + 1).take(matches[i]).for_each(|x| *x += n);
+        }
+        counts.into_iter().sum()
+    }
+
+    #[test]
+    fn test_scoring_rules() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let scores = |rule: ScoringRule| (0..6).map(|m| rule.score(m)).collect::<Vec<_>>();
+        assert_eq!(scores(ScoringRule::Doubling), [0, 1, 2, 4, 8, 16]);
+        assert_eq!(scores(ScoringRule::Linear), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(scores(ScoringRule::Fibonacci), [0, 1, 1, 2, 3, 5]);
+        assert_eq!(scores("table:0,10,15".parse().unwrap()), [0, 10, 15, 15, 15, 15]);
+        assert_eq!(ScoringRule::Doubling.score(40), u32::MAX);
+        let win = (1..=40).map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+        let big = [format!("Card 1: {} | {}", win, win), format!("Card 2: {} | {}", win, win)];
+        assert_eq!(Ok(2 * u32::MAX as u64), task1(&big));
+        assert_eq!(Ok(4 + 2 + 2 + 1), task1_with(&lines, &ScoringRule::Linear, Duplicates::Once));
+        assert_eq!(Ok(3 + 1 + 1 + 1), task1_with(&lines, &"fibonacci".parse().unwrap(), Duplicates::Once));
+        assert_eq!("table:1,x".parse::<ScoringRule>(), Err(Error::InvalidRule("table:1,x".to_string())));
+        assert_eq!("squares".parse::<ScoringRule>(), Err(Error::InvalidRule("squares".to_string())));
+    }
+
+    #[test]
+    fn test_reward_rules() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        assert_eq!(Ok(30), task2_with(&lines, RewardRule::Next, Duplicates::Once));
+        assert_eq!(RewardRule::Next.cascade(&[4, 2, 2, 1, 0, 0]), naive_cascade(&[4, 2, 2, 1, 0, 0]));
+        assert_eq!(RewardRule::Previous.cascade(&[0, 0, 1, 2, 2, 4]), naive_cascade(&[4, 2, 2, 1, 0, 0]));
+        // cards 3 and 4 wrap onto card 1 after it was scored, giving instances [4, 2, 1, 2]
+        assert_eq!(RewardRule::WrapAround.cascade(&[1, 0, 2, 1]), 9);
+        assert_eq!(RewardRule::WrapAround.cascade(&[5]), 6);
+        assert_eq!("sideways".parse::<RewardRule>(), Err(Error::InvalidRule("sideways".to_string())));
+    }
+
+    #[test]
+    fn test_cascade() {
+        let decks: [&[usize]; 5] = [&[], &[0, 0], &[4, 2, 2, 1, 0, 0], &[1, 3, 0, 5, 2, 8, 1, 1, 0, 4, 0, 0], &[2, 0, 6, 1, 1, 1, 9, 3, 0, 0, 2]];
+        for deck in decks {
+            let mut cascade = Cascade::default();
+            deck.iter().for_each(|m| { cascade.push(*m); });
+            assert_eq!(cascade.total(), naive_cascade(deck));
+            assert!(cascade.ring.len() <= deck.iter().copied().max().unwrap_or(0));
+        }
+    }
+
+    #[test]
+    fn test_number_set() {
+        let small = "41 48 83 86 17".parse::<NumberSet>().unwrap();
+        let mixed = "83 1000 86 127 128 4000000000 1000".parse::<NumberSet>().unwrap();
+        assert_eq!(mixed.len(), 6);
+        assert!(mixed.contains(127) && mixed.contains(128) && mixed.contains(4000000000));
+        assert!(!mixed.contains(129) && !mixed.contains(0));
+        assert_eq!(small.intersection_count(&mixed), 2);
+        let large = "128 999 1000 4000000000".parse::<NumberSet>().unwrap();
+        assert_eq!(mixed.intersection_count(&large), 3);
+        assert!("".parse::<NumberSet>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        assert_eq!(Vec::<(usize, Issue)>::new(), validate(&lines));
+        let lines = [
+            "Card 1: 41 48 41 | 83 86 48 48",
+            "Card 3: 13 32 | 61 30",
+            "Card 3: 1 21 | 69 82",
+            "Card 4: 5 | 5 5 5",
+        ];
+        assert_eq!(vec![
+            (1, Issue::DuplicateWinning(41)),
+            (1, Issue::DuplicateHeld(48)),
+            (2, Issue::NonSequentialId { expected: 2, found: 3 }),
+            (3, Issue::DuplicateId(3)),
+            (4, Issue::DuplicateHeld(5)),
+            (4, Issue::DuplicateHeld(5)),
+        ], validate(&lines));
+        assert_eq!(Issue::NonSequentialId { expected: 2, found: 3 }.to_string(), "expected card 2 but found card 3");
+        assert_eq!(Ok(1 + 1), task1_with(&lines, &ScoringRule::Doubling, Duplicates::Once));
+        assert_eq!(Ok(2 + 4), task1_with(&lines, &ScoringRule::Doubling, Duplicates::Multiple));
+        assert_eq!(Ok(5), task2_with(&lines, RewardRule::Next, Duplicates::Once));
+        assert_eq!(Ok(6), task2_with(&lines, RewardRule::Next, Duplicates::Multiple));
+        assert_eq!(Ok(2 + 4), task1(&lines));
+        assert_eq!(Ok(6), task2(&lines));
+        let lines = ["Card 1: 1 | 2", "Card 2 1 | 2", "Card x: 1 | 2", "Card 4: 1 1 | 2"];
+        assert_eq!(vec![
+            (2, Issue::Unparsable(Error::FormatError)),
+            (3, Issue::Unparsable("x".parse::<u32>().unwrap_err().into())),
+            (4, Issue::DuplicateWinning(1)),
+        ], validate(&lines));
+        assert_eq!(Issue::Unparsable(Error::FormatError).to_string(), "unparsable card: Format error");
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let card = "Card 1: 200 5 70000 | 70000 5 6 200 201".parse::<Card>().unwrap();
+        assert_eq!(card.count(), 3);
+        assert_eq!(card.score(), 4);
+    }
+
 }
@@ -0,0 +1,16 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day05::generator::generate;
+use day05::task::{task1, task2};
+
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("almanac");
+    for size in [10, 100, 1000] {
+        let lines = generate(size, 0);
+        group.bench_with_input(BenchmarkId::new("task1", size), &lines, |b, lines| b.iter(|| task1(lines)));
+        group.bench_with_input(BenchmarkId::new("task2", size), &lines, |b, lines| b.iter(|| task2(lines)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
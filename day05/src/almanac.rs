@@ -0,0 +1,415 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+use crate::task::{Error, Result};
+
+type Entries = Vec<(Range<u64>, Range<u64>)>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryMap {
+    pub source: String,
+    pub target: String,
+    // (source range, destination range), sorted by source start
+    entries: Entries,
+}
+
+impl CategoryMap {
+    pub fn new(source: &str, target: &str, mut entries: Entries) -> Self {
+        entries.sort_by_key(|(r, _)| r.start);
+        Self { source: source.to_string(), target: target.to_string(), entries }
+    }
+
+    pub fn entries(&self) -> &[(Range<u64>, Range<u64>)] {
+        &self.entries
+    }
+
+    pub fn apply(&self, value: u64) -> u64 {
+        let m = self.entries.partition_point(|(range_from, _)| range_from.end <= value);
+        match self.entries.get(m) {
+            Some((range_from, range_to)) if range_from.contains(&value) => value + range_to.start - range_from.start,
+            _ => value,
+        }
+    }
+
+    // split the range at entry boundaries and translate each piece
+    pub fn apply_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        let mut pieces = Vec::new();
+        let mut start = range.start;
+        let mut m = self.entries.partition_point(|(range_from, _)| range_from.end <= start);
+        while start < range.end {
+            match self.entries.get(m) {
+                Some((range_from, range_to)) if range_from.start <= start => {
+                    let end = range_from.end.min(range.end);
+                    pieces.push(start + range_to.start - range_from.start..end + range_to.start - range_from.start);
+                    start = end;
+                    m += 1;
+                },
+                Some((range_from, _)) => {
+                    let end = range_from.start.min(range.end);
+                    pieces.push(start..end);
+                    start = end;
+                },
+                None => {
+                    pieces.push(start..range.end);
+                    start = range.end;
+                },
+            }
+        }
+        pieces
+    }
+
+    // every value mapped onto the given one, in ascending order
+    pub fn inverse(&self, value: u64) -> Vec<u64> {
+        let mut found = self.entries.iter()
+            .filter(|(_, range_to)| range_to.contains(&value))
+            .map(|(range_from, range_to)| value - range_to.start + range_from.start)
+            .collect::<Vec<_>>();
+        if !self.entries.iter().any(|(range_from, _)| range_from.contains(&value)) {
+            found.push(value);
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    // this map followed by next, as one map from this source to next's target
+    pub fn compose(&self, next: &CategoryMap) -> CategoryMap {
+        let mut entries: Entries = Vec::new();
+        let mut start = 0;
+        let mut pieces = Vec::new();
+        for (range_from, range_to) in &self.entries {
+            pieces.push((start..range_from.start, start..range_from.start));
+            pieces.push((range_from.clone(), range_to.clone()));
+            start = range_from.end;
+        }
+        pieces.push((start..u64::MAX, start..u64::MAX));
+        for (range_from, range_to) in pieces.into_iter().filter(|(r, _)| !r.is_empty()) {
+            let mut src = range_from.start;
+            for dest in next.apply_range(range_to) {
+                let len = dest.end - dest.start;
+                match entries.last_mut() {
+                    Some((last_from, last_to)) if last_from.end == src && last_to.end == dest.start => {
+                        last_from.end += len;
+                        last_to.end += len;
+                    },
+                    _ => entries.push((src..src + len, dest)),
+                }
+                src += len;
+            }
+        }
+        entries.retain(|(range_from, range_to)| range_from.start != range_to.start);
+        CategoryMap::new(&self.source, &next.target, entries)
+    }
+}
+
+// the almanac entry format, "dest src len" per entry
+impl fmt::Display for CategoryMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}-to-{} map:", self.source, self.target)?;
+        for (range_from, range_to) in &self.entries {
+            writeln!(f, "{} {} {}", range_to.start, range_from.start, range_from.end - range_from.start)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    Warn,
+    Strict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapReport {
+    pub name: String,
+    pub entries: usize,
+    // pairs of entries sharing source values, and sharing destination values
+    pub overlapping_sources: Vec<(Range<u64>, Range<u64>)>,
+    pub overlapping_destinations: Vec<(Range<u64>, Range<u64>)>,
+    pub span: Range<u64>,
+    pub covered: u64,
+    pub gaps: usize,
+}
+
+impl MapReport {
+    pub fn is_clean(&self) -> bool {
+        self.overlapping_sources.is_empty() && self.overlapping_destinations.is_empty()
+    }
+}
+
+impl fmt::Display for MapReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} entries cover {} of {}..{} with {} gaps", self.name, self.entries, self.covered, self.span.start, self.span.end, self.gaps)?;
+        for (a, b) in &self.overlapping_sources {
+            write!(f, "\n  overlapping sources {}..{} and {}..{}", a.start, a.end, b.start, b.end)?;
+        }
+        for (a, b) in &self.overlapping_destinations {
+            write!(f, "\n  overlapping destinations {}..{} and {}..{}", a.start, a.end, b.start, b.end)?;
+        }
+        Ok(())
+    }
+}
+
+// pairs of ranges sharing values
+fn overlaps(mut ranges: Vec<Range<u64>>) -> Vec<(Range<u64>, Range<u64>)> {
+    ranges.retain(|r| !r.is_empty());
+    ranges.sort_by_key(|r| (r.start, r.end));
+    let mut found = Vec::new();
+    let mut active: Vec<Range<u64>> = Vec::new();
+    for range in ranges {
+        active.retain(|a| a.end > range.start);
+        found.extend(active.iter().map(|a| (a.clone(), range.clone())));
+        active.push(range);
+    }
+    found
+}
+
+impl CategoryMap {
+    pub fn check(&self) -> MapReport {
+        let sources = self.entries.iter().map(|(r, _)| r.clone()).filter(|r| !r.is_empty()).collect::<Vec<_>>();
+        let span = match (sources.iter().map(|r| r.start).min(), sources.iter().map(|r| r.end).max()) {
+            (Some(start), Some(end)) => start..end,
+            _ => 0..0,
+        };
+        let (mut covered, mut gaps, mut end) = (0, 0, span.start);
+        for range in &sources {
+            if range.start > end {
+                gaps += 1;
+            }
+            covered += range.end.saturating_sub(range.start.max(end));
+            end = end.max(range.end);
+        }
+        MapReport {
+            name: format!("{}-to-{}", self.source, self.target),
+            entries: self.entries.len(),
+            overlapping_sources: overlaps(sources),
+            overlapping_destinations: overlaps(self.entries.iter().map(|(_, r)| r.clone()).collect()),
+            span,
+            covered,
+            gaps,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    // in chain order, each target being the next source
+    maps: Vec<CategoryMap>,
+}
+
+fn parse_numbers(s: &str) -> Result<Vec<u64>> {
+    Ok(s.split_ascii_whitespace().map(|n| n.parse::<u64>()).collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+fn chain(maps: Vec<CategoryMap>) -> Result<Vec<CategoryMap>> {
+    let mut by_source = HashMap::new();
+    for (i, map) in maps.iter().enumerate() {
+        if by_source.insert(map.source.clone(), i).is_some() {
+            return Err(Error::DuplicateMap(map.source.clone()));
+        }
+    }
+    let mut targets = HashSet::new();
+    for map in &maps {
+        if !targets.insert(map.target.as_str()) {
+            return Err(Error::BrokenChain(map.source.clone()));
+        }
+    }
+    let starts = maps.iter().filter(|map| !targets.contains(map.source.as_str())).collect::<Vec<_>>();
+    if starts.len() > 1 || (starts.is_empty() && !maps.is_empty()) {
+        return Err(Error::BrokenChain(starts.get(1).unwrap_or(&&maps[0]).source.clone()));
+    }
+    let mut order = Vec::new();
+    let mut next = starts.first().map(|map| map.source.clone());
+    while let Some(i) = next.and_then(|source| by_source.remove(&source)) {
+        order.push(i);
+        next = Some(maps[i].target.clone());
+    }
+    if let Some(i) = (0..maps.len()).find(|i| !order.contains(i)) {
+        return Err(Error::BrokenChain(maps[i].source.clone()));
+    }
+    let mut maps = maps.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(order.into_iter().filter_map(|i| maps[i].take()).collect())
+}
+
+impl Almanac {
+    pub fn new(seeds: Vec<u64>, maps: Vec<CategoryMap>) -> Result<Self> {
+        Ok(Self { seeds, maps: chain(maps)? })
+    }
+
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Self> {
+        let mut seeds = None;
+        let mut maps = Vec::new();
+        let mut current: Option<(String, String, Entries)> = None;
+        for line in lines.iter().map(|s| s.as_ref().trim()).filter(|s| !s.is_empty()) {
+            if let Some(rest) = line.strip_prefix("seeds:") {
+                seeds = Some(parse_numbers(rest)?);
+            } else if let Some(header) = line.strip_suffix("map:") {
+                let (source, target) = header.trim().split_once("-to-").ok_or(Error::FormatError)?;
+                maps.extend(current.take().map(|(source, target, entries)| CategoryMap::new(&source, &target, entries)));
+                current = Some((source.to_string(), target.to_string(), Vec::new()));
+            } else {
+                let (_, _, entries) = current.as_mut().ok_or(Error::FormatError)?;
+                let [dest, src, len] = parse_numbers(line)?[..] else { return Err(Error::FormatError) };
+                let src_end = src.checked_add(len).ok_or(Error::FormatError)?;
+                let dest_end = dest.checked_add(len).ok_or(Error::FormatError)?;
+                entries.push((src..src_end, dest..dest_end));
+            }
+        }
+        maps.extend(current.map(|(source, target, entries)| CategoryMap::new(&source, &target, entries)));
+        Self::new(seeds.ok_or(Error::FormatError)?, maps)
+    }
+
+    pub fn maps(&self) -> &[CategoryMap] {
+        &self.maps
+    }
+
+    pub fn categories(&self) -> Vec<&str> {
+        self.maps.iter().map(|map| map.source.as_str()).chain(self.maps.last().map(|map| map.target.as_str())).collect()
+    }
+
+    // in strict mode the first map with overlapping entries is an error
+    pub fn validate(&self, mode: Validation) -> Result<Vec<MapReport>> {
+        let reports = self.maps.iter().map(|map| map.check()).collect::<Vec<_>>();
+        if mode == Validation::Strict {
+            if let Some(report) = reports.iter().find(|report| !report.is_clean()) {
+                return Err(Error::OverlappingEntries(report.name.clone()));
+            }
+        }
+        Ok(reports)
+    }
+
+    pub fn composed(&self) -> Option<CategoryMap> {
+        self.maps.split_first().map(|(first, rest)| rest.iter().fold(first.clone(), |acc, map| acc.compose(map)))
+    }
+
+    fn position(&self, category: &str) -> Result<usize> {
+        self.categories().iter().position(|c| *c == category).ok_or(Error::UnknownCategory(category.to_string()))
+    }
+
+    // values of the target category corresponding to the given source value;
+    // going back up the chain there may be several or none
+    pub fn convert(&self, from: &str, to: &str, value: u64) -> Result<Vec<u64>> {
+        let (i, j) = (self.position(from)?, self.position(to)?);
+        if i <= j {
+            return Ok(vec![self.maps[i..j].iter().fold(value, |value, map| map.apply(value))]);
+        }
+        let mut values = vec![value];
+        for map in self.maps[j..i].iter().rev() {
+            values = values.into_iter().flat_map(|value| map.inverse(value)).collect();
+            values.sort_unstable();
+            values.dedup();
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str =
+"seeds: 79 14 55 13
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4";
+
+    #[test]
+    fn test_parse() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let almanac = Almanac::parse(&lines).unwrap();
+        assert_eq!(almanac.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(almanac.categories(), vec!["seed", "soil", "fertilizer", "water"]);
+        assert_eq!(almanac.maps()[0].entries()[0], (50..98, 52..100));
+        assert_eq!(almanac.convert("seed", "water", 79), Ok(vec![81]));
+        assert_eq!(almanac.convert("seed", "fertilizer", 14), Ok(vec![53]));
+        assert_eq!(almanac.convert("soil", "soil", 7), Ok(vec![7]));
+        assert_eq!(almanac.convert("fertilizer", "seed", 53), Ok(vec![14]));
+        assert_eq!(almanac.convert("water", "seed", 81), Ok(vec![79]));
+        assert_eq!(almanac.convert("seed", "humidity", 1), Err(Error::UnknownCategory("humidity".to_string())));
+    }
+
+    #[test]
+    fn test_broken_chain() {
+        let map = |source, target| CategoryMap::new(source, target, vec![]);
+        assert_eq!(Almanac::new(vec![], vec![map("a", "b"), map("c", "d")]), Err(Error::BrokenChain("c".to_string())));
+        assert_eq!(Almanac::new(vec![], vec![map("a", "b"), map("b", "a")]), Err(Error::BrokenChain("a".to_string())));
+        assert_eq!(Almanac::new(vec![], vec![map("a", "b"), map("b", "c"), map("c", "b")]), Err(Error::BrokenChain("c".to_string())));
+        assert_eq!(Almanac::new(vec![], vec![map("a", "b"), map("a", "c")]), Err(Error::DuplicateMap("a".to_string())));
+        let almanac = Almanac::new(vec![], vec![map("c", "d"), map("b", "c"), map("a", "b")]).unwrap();
+        assert_eq!(almanac.categories(), vec!["a", "b", "c", "d"]);
+        assert_eq!(Almanac::parse(&["seeds: 1", "1 2 3"]), Err(Error::FormatError));
+        assert_eq!(Almanac::parse(&["seeds: 1", "a-b map:"]), Err(Error::FormatError));
+        assert_eq!(Almanac::parse(&["seeds: 1", "a-to-b map:", "18446744073709551615 0 50"]), Err(Error::FormatError));
+        assert_eq!(Almanac::parse(&["seeds: 1", "a-to-b map:", "0 18446744073709551615 50"]), Err(Error::FormatError));
+    }
+
+    #[test]
+    fn test_apply_range() {
+        let map = CategoryMap::new("a", "b", vec![(30..40, 50..60), (10..20, 100..110), (20..25, 0..5)]);
+        assert_eq!(map.apply_range(0..5), vec![0..5]);
+        assert_eq!(map.apply_range(5..45), vec![5..10, 100..110, 0..5, 25..30, 50..60, 40..45]);
+        assert_eq!(map.apply_range(12..22), vec![102..110, 0..2]);
+        assert_eq!(map.apply_range(33..34), vec![53..54]);
+        assert_eq!(map.apply_range(7..7), vec![]);
+        assert_eq!(CategoryMap::new("a", "b", vec![]).apply_range(3..9), vec![3..9]);
+    }
+
+    #[test]
+    fn test_compose() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let almanac = Almanac::parse(&lines).unwrap();
+        let composed = almanac.composed().unwrap();
+        assert_eq!((composed.source.as_str(), composed.target.as_str()), ("seed", "water"));
+        for value in 0..120 {
+            assert_eq!(vec![composed.apply(value)], almanac.convert("seed", "water", value).unwrap());
+            assert_eq!(composed.inverse(value), almanac.convert("water", "seed", value).unwrap());
+        }
+        assert_eq!(composed.apply(u64::MAX - 1), u64::MAX - 1);
+        let a = CategoryMap::new("a", "b", vec![(0..10, 20..30)]);
+        let b = CategoryMap::new("b", "c", vec![(25..35, 0..10), (0..5, 25..30)]);
+        let ab = a.compose(&b);
+        assert_eq!(ab.entries(), [(0..5, 20..25), (5..10, 0..5), (25..35, 0..10)]);
+        assert_eq!(ab.to_string(), "a-to-c map:\n20 0 5\n0 5 5\n0 25 10\n");
+        assert_eq!(ab.inverse(3), vec![8, 28]);
+        assert_eq!(ab.apply_range(0..30), vec![20..25, 0..5, 10..25, 0..5]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let almanac = Almanac::parse(&lines).unwrap();
+        let reports = almanac.validate(Validation::Strict).unwrap();
+        assert!(reports.iter().all(|report| report.is_clean()));
+        assert_eq!((reports[0].span.clone(), reports[0].covered, reports[0].gaps), (50..100, 50, 0));
+        assert_eq!(reports[0].to_string(), "seed-to-soil: 2 entries cover 50 of 50..100 with 0 gaps");
+        let map = CategoryMap::new("a", "b", vec![(0..10, 100..110), (5..8, 200..203), (6..12, 105..111), (20..30, 0..10), (40..41, 300..301)]);
+        let almanac = Almanac::new(vec![], vec![map]).unwrap();
+        assert_eq!(almanac.validate(Validation::Strict), Err(Error::OverlappingEntries("a-to-b".to_string())));
+        let report = &almanac.validate(Validation::Warn).unwrap()[0];
+        assert_eq!(report.overlapping_sources, vec![(0..10, 5..8), (0..10, 6..12), (5..8, 6..12)]);
+        assert_eq!(report.overlapping_destinations, vec![(100..110, 105..111)]);
+        assert_eq!((report.span.clone(), report.covered, report.gaps), (0..41, 23, 2));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let map = CategoryMap::new("a", "b", vec![(10..20, 100..110), (20..25, 0..5)]);
+        assert_eq!(map.inverse(3), vec![3, 23]);
+        assert_eq!(map.inverse(12), vec![]);
+        assert_eq!(map.inverse(105), vec![15, 105]);
+        assert_eq!(map.inverse(50), vec![50]);
+    }
+}
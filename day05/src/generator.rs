@@ -0,0 +1,51 @@
+use std::ops::Range;
+
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn range(&mut self, range: Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i as u64 + 1) as usize);
+        }
+    }
+}
+
+const CATEGORIES: [&str; 8] = ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+
+pub fn generate(size: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let n = size.max(1) as u64;
+    let seeds = (0..n).map(|_| format!("{} {}", rng.range(0..10 * n), rng.range(1..2 * n + 1))).collect::<Vec<_>>();
+    let mut lines = vec![format!("seeds: {}", seeds.join(" "))];
+    for w in CATEGORIES.windows(2) {
+        lines.push(String::new());
+        lines.push(format!("{}-to-{} map:", w[0], w[1]));
+        let mut start = 0;
+        let mut entries = (0..rng.range(1..n + 1)).map(|_| {
+            start += rng.range(0..n + 1);
+            let len = rng.range(1..3 * n + 1);
+            let entry = format!("{} {} {}", rng.range(0..20 * n), start, len);
+            start += len;
+            entry
+        }).collect::<Vec<_>>();
+        rng.shuffle(&mut entries);
+        lines.extend(entries);
+    }
+    lines
+}
@@ -0,0 +1,3 @@
+pub mod almanac;
+pub mod generator;
+pub mod task;
@@ -1,32 +1,110 @@
 mod input;
-mod task;
 
+use day05::almanac::{Almanac, Validation};
+use day05::generator::generate;
 use input::{read_file, read_stdin};
+use common::profile::{measure, report};
+use common::store::{read_store, record_store};
 use std::io;
 use std::env;
-use task::*;
+use day05::task::*;
 
 enum Mode {
     File(String),
-    Stdin
+    Stdin,
+    Store(Option<String>),
+    Gen(usize, u64),
+}
+
+fn parse_arg<T: std::str::FromStr>(arg: Option<String>, default: T) -> io::Result<T> {
+    match arg {
+        Some(arg) => arg.parse().map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid argument {}", arg))),
+        None => Ok(default),
+    }
 }
 
 fn main() -> io::Result<()> {
-    let mode = env::args().nth(1).map(|arg| Mode::File(arg)).unwrap_or(Mode::Stdin);
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let conversion = match args.first() {
+        Some(arg) if arg == "convert" && args.len() > 3 => {
+            let mut conversion = args.drain(..4).skip(1);
+            Some((conversion.next().unwrap(), conversion.next().unwrap(), parse_arg(conversion.next(), 0u64)?))
+        },
+        _ => None,
+    };
+    let validation = match args.iter().position(|arg| arg == "--strict" || arg == "--warn") {
+        Some(i) if args.remove(i) == "--strict" => Some(Validation::Strict),
+        Some(_) => Some(Validation::Warn),
+        None => None,
+    };
+    let dump = args.first().is_some_and(|arg| arg == "dump");
+    if dump {
+        args.remove(0);
+    }
+    let mut args = args.into_iter();
+    let mode = match args.next() {
+        Some(arg) if arg == "gen" => Mode::Gen(parse_arg(args.next(), 10)?, parse_arg(args.next(), 0)?),
+        Some(arg) if arg == "--profile" => match args.next() {
+            Some(profile) => Mode::Store(Some(profile)),
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "--profile needs a name")),
+        },
+        Some(arg) if arg == "--record" => return record_store(env!("CARGO_PKG_NAME"), args.next().as_deref()),
+        Some(arg) if arg == "-" => Mode::Stdin,
+        Some(arg) => Mode::File(arg),
+        None => Mode::Store(None),
+    };
     let data = match mode {
         Mode::File(file_path) => read_file(&file_path)?,
-        Mode::Stdin => read_stdin()?
+        Mode::Stdin => read_stdin()?,
+        Mode::Store(profile) => match read_store(env!("CARGO_PKG_NAME"), profile.as_deref())? {
+            Some(data) => data,
+            None if profile.is_none() => read_stdin()?,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "stored input not found")),
+        },
+        Mode::Gen(size, seed) => {
+            for line in generate(size, seed) {
+                println!("{}", line);
+            }
+            return Ok(());
+        }
     };
 
-    match task1(&data) {
+    if let Some(mode) = validation {
+        let reports = Almanac::parse(&data).and_then(|almanac| almanac.validate(mode))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        for report in reports {
+            eprintln!("{}", report);
+        }
+    }
+
+    if dump {
+        let almanac = Almanac::parse(&data).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        if let Some(composed) = almanac.composed() {
+            print!("{}", composed);
+        }
+        return Ok(());
+    }
+
+    if let Some((from, to, value)) = conversion {
+        let values = Almanac::parse(&data).and_then(|almanac| almanac.convert(&from, &to, value))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        println!("{}", values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" "));
+        return Ok(());
+    }
+
+    let (result, usage) = measure(|| task1(&data));
+    match result {
         Ok(result) => println!("result1 {}", result),
         Err(error) => println!("error {}", error)
     }
+    report(env!("CARGO_PKG_NAME"), "task1", usage);
 
-    match task2(&data) {
+    let (result, usage) = measure(|| task2(&data));
+    match result {
         Ok(result) => println!("result2 {}", result),
         Err(error) => println!("error {}", error)
     }
+    report(env!("CARGO_PKG_NAME"), "task2", usage);
 
     Ok(())
 }
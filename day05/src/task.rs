@@ -1,4 +1,5 @@
 use std::num::ParseIntError;
+use crate::almanac::Almanac;
 
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
@@ -6,77 +7,40 @@ pub enum Error {
     ParseIntError(#[from] ParseIntError),
     #[error("Format error")]
     FormatError,
+    #[error("Unknown category {0}")]
+    UnknownCategory(String),
+    #[error("Broken category chain at {0}")]
+    BrokenChain(String),
+    #[error("Duplicate map from {0}")]
+    DuplicateMap(String),
+    #[error("Overlapping entries in {0} map")]
+    OverlappingEntries(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    let seeds = lines[0].as_ref().split_ascii_whitespace().skip(1).map(|n| n.parse::<u64>().unwrap()).collect::<Vec<_>>();
-    let mut pos = 1;
-    let mut parse_map = || {
-        pos += 2;
-        let mut map = Vec::new();
-        while pos < lines.len() && !lines[pos].as_ref().is_empty() {
-            let nums = lines[pos].as_ref().split_ascii_whitespace().map(|n| n.parse::<u64>().unwrap()).collect::<Vec<_>>();
-            map.push(((nums[1]..nums[1]+nums[2]), (nums[0]..nums[0]+nums[2])));
-            pos += 1;
-        }
-        map.sort_by_key(|(r, _)| r.start);
-        map
-    };
-    let maps = (0..7).map(|_| parse_map()).collect::<Vec<_>>();
-    let mut ans = u64::MAX;
-    for mut seed in seeds {
-        for map in &maps {
-            let m = map.partition_point(|(range_from, _)| range_from.end <= seed);
-            if let Some((range_from, range_to)) = map.get(m) {
-                if range_from.contains(&seed) {
-                    seed = seed + range_to.start - range_from.start;
-                }
-            }
-        }
-        ans = ans.min(seed);
-    }
-    Ok(ans)
+    let almanac = Almanac::parse(lines)?;
+    almanac.seeds.iter().map(|seed| almanac.maps().iter().fold(*seed, |seed, map| map.apply(seed))).min().ok_or(Error::FormatError)
 }
 
 pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    // let seeds = lines[0].as_ref().split_ascii_whitespace().skip(1).map(|n| n.parse::<u64>().unwrap()).collect::<Vec<_>>();
-    let seeds = lines[0].as_ref().split_ascii_whitespace().skip(1).map(|n| n.parse::<u64>().unwrap()).collect::<Vec<_>>();
-    let seed_ranges = seeds.chunks(2).map(|chunk| (chunk[0]..chunk[0]+chunk[1])).collect::<Vec<_>>();
-    let mut pos = 1;
-    let mut parse_map = || {
-        pos += 2;
-        let mut map = Vec::new();
-        while pos < lines.len() && !lines[pos].as_ref().is_empty() {
-            let nums = lines[pos].as_ref().split_ascii_whitespace().map(|n| n.parse::<u64>().unwrap()).collect::<Vec<_>>();
-            map.push(((nums[1]..nums[1]+nums[2]), (nums[0]..nums[0]+nums[2])));
-            pos += 1;
-        }
-        map.sort_by_key(|(r, _)| r.start);
-        map
-    };
-    let maps = (0..7).map(|_| parse_map()).collect::<Vec<_>>();
-    let mut ans = u64::MAX;
-    for seed_range in seed_ranges {
-        for mut seed in seed_range {
-            for map in &maps {
-                let m = map.partition_point(|(range_from, _)| range_from.end <= seed);
-                if let Some((range_from, range_to)) = map.get(m) {
-                    if range_from.contains(&seed) {
-                        seed = seed + range_to.start - range_from.start;
-                    }
-                }
-            }
-            ans = ans.min(seed);
-        }
+    let almanac = Almanac::parse(lines)?;
+    let mut ranges = almanac.seeds.chunks(2).map(|chunk| match chunk {
+        [start, len] => Ok(*start..start.checked_add(*len).ok_or(Error::FormatError)?),
+        _ => Err(Error::FormatError),
+    }).collect::<Result<Vec<_>>>()?;
+    for map in almanac.maps() {
+        ranges = ranges.into_iter().flat_map(|range| map.apply_range(range)).collect();
     }
-    Ok(ans)
+    ranges.into_iter().map(|range| range.start).min().ok_or(Error::FormatError)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::generate;
+    use proptest::prelude::*;
     const DATA: &str =
 "seeds: 79 14 55 13
 
@@ -122,6 +86,62 @@ humidity-to-location map:
     fn test_task2() {
         let lines = DATA.lines().collect::<Vec<_>>();
         assert_eq!(Ok(46), task2(&lines));
+        let mut lines = lines;
+        lines[0] = "seeds: 18446744073709551615 2";
+        assert_eq!(Err(Error::FormatError), task2(&lines));
+        lines[0] = "seeds: 79 14 55";
+        assert_eq!(Err(Error::FormatError), task2(&lines));
+        lines[0] = "seeds: 18446744073709551614 1";
+        assert!(task2(&lines).is_ok());
+    }
+
+    fn naive_seeds(lines: &[String]) -> Vec<u64> {
+        lines[0].split_ascii_whitespace().skip(1).map(|n| n.parse().unwrap()).collect()
     }
 
+    fn naive_maps(lines: &[String]) -> Vec<Vec<(u64, u64, u64)>> {
+        lines.split(|line| line.is_empty()).skip(1).map(|block| {
+            block[1..].iter().map(|line| {
+                let nums = line.split_ascii_whitespace().map(|n| n.parse().unwrap()).collect::<Vec<u64>>();
+                (nums[0], nums[1], nums[2])
+            }).collect()
+        }).collect()
+    }
+
+    fn naive_location(maps: &[Vec<(u64, u64, u64)>], mut seed: u64) -> u64 {
+        for map in maps {
+            if let Some((dest, src, _)) = map.iter().find(|(_, src, len)| (*src..src + len).contains(&seed)) {
+                seed = seed - src + dest;
+            }
+        }
+        seed
+    }
+
+    fn almanac_strategy() -> impl Strategy<Value = Vec<String>> {
+        (1usize..8, any::<u64>()).prop_map(|(size, seed)| generate(size, seed))
+    }
+
+    proptest! {
+        #[test]
+        fn composed_matches_naive(lines in almanac_strategy(), value in 0u64..200) {
+            let composed = Almanac::parse(&lines).unwrap().composed().unwrap();
+            let location = naive_location(&naive_maps(&lines), value);
+            prop_assert_eq!(composed.apply(value), location);
+            prop_assert!(composed.inverse(location).contains(&value));
+        }
+
+        #[test]
+        fn task1_matches_naive(lines in almanac_strategy()) {
+            let maps = naive_maps(&lines);
+            let expected = naive_seeds(&lines).into_iter().map(|seed| naive_location(&maps, seed)).min().unwrap();
+            prop_assert_eq!(Ok(expected), task1(&lines));
+        }
+
+        #[test]
+        fn task2_matches_naive(lines in almanac_strategy()) {
+            let maps = naive_maps(&lines);
+            let expected = naive_seeds(&lines).chunks(2).flat_map(|c| c[0]..c[0] + c[1]).map(|seed| naive_location(&maps, seed)).min().unwrap();
+            prop_assert_eq!(Ok(expected), task2(&lines));
+        }
+    }
 }
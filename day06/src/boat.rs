@@ -0,0 +1,159 @@
+use std::fmt;
+use crate::task::{Error, Result};
+
+// distance must strictly increase with the hold time up to its peak and
+// never increase after it
+pub trait BoatModel: fmt::Debug {
+    fn distance(&self, hold: u64, time: u64) -> u128;
+}
+
+// speed grows by rate mm/ms for each ms held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Linear {
+    pub rate: u64,
+}
+
+impl Default for Linear {
+    fn default() -> Self {
+        Self { rate: 1 }
+    }
+}
+
+impl BoatModel for Linear {
+    fn distance(&self, hold: u64, time: u64) -> u128 {
+        (self.rate as u128 * hold as u128).saturating_mul(time.saturating_sub(hold) as u128)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capped {
+    pub rate: u64,
+    pub max_speed: u64,
+}
+
+impl BoatModel for Capped {
+    fn distance(&self, hold: u64, time: u64) -> u128 {
+        let speed = (self.rate as u128 * hold as u128).min(self.max_speed as u128);
+        speed.saturating_mul(time.saturating_sub(hold) as u128)
+    }
+}
+
+// speed drops by friction mm/ms every ms after release until the boat stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Friction {
+    pub rate: u64,
+    pub friction: u64,
+}
+
+impl BoatModel for Friction {
+    fn distance(&self, hold: u64, time: u64) -> u128 {
+        let speed = self.rate as u128 * hold as u128;
+        let (f, n) = (self.friction as u128, time.saturating_sub(hold) as u128);
+        let moving = if f == 0 { n } else { n.min(speed.div_ceil(f)) };
+        // speed lost by the last moving ms, below the release speed so it cannot overflow
+        let lost = f * moving.saturating_sub(1);
+        // moving * (speed - lost / 2), kept exact when lost is odd (then moving is even)
+        if lost % 2 == 0 {
+            moving.saturating_mul(speed - lost / 2)
+        } else {
+            (moving / 2).saturating_mul(speed.saturating_add(speed - lost))
+        }
+    }
+}
+
+// "linear:<rate>", "capped:<rate>,<max speed>" or "friction:<rate>,<friction>"
+pub fn parse_model(s: &str) -> Result<Box<dyn BoatModel>> {
+    let (name, params) = s.split_once(':').unwrap_or((s, ""));
+    let params = params.split(',').filter(|p| !p.is_empty()).map(|p| p.trim().parse::<u64>()).collect::<std::result::Result<Vec<_>, _>>()?;
+    match (name, &params[..]) {
+        ("linear", []) => Ok(Box::new(Linear::default())),
+        ("linear", &[rate]) => Ok(Box::new(Linear { rate })),
+        ("capped", &[rate, max_speed]) => Ok(Box::new(Capped { rate, max_speed })),
+        ("friction", &[rate, friction]) => Ok(Box::new(Friction { rate, friction })),
+        _ => Err(Error::FormatError),
+    }
+}
+
+// number of hold times in 0..=time beating distance, by binary search around the peak
+pub fn winways_with<M: BoatModel + ?Sized>(model: &M, time: u64, distance: u64) -> u64 {
+    let d = |hold: u64| model.distance(hold, time);
+    let (mut l, mut r) = (0, time);
+    while l < r {
+        let m = l + (r - l) / 2;
+        if d(m + 1) > d(m) {
+            l = m + 1;
+        } else {
+            r = m;
+        }
+    }
+    let peak = l;
+    if d(peak) <= distance as u128 {
+        return 0;
+    }
+    let (mut l, mut r) = (0, peak);
+    while l < r {
+        let m = l + (r - l) / 2;
+        if d(m) > distance as u128 {
+            r = m;
+        } else {
+            l = m + 1;
+        }
+    }
+    let first = l;
+    let (mut l, mut r) = (peak, time);
+    while l < r {
+        let m = r - (r - l) / 2;
+        if d(m) > distance as u128 {
+            l = m;
+        } else {
+            r = m - 1;
+        }
+    }
+    l - first + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn brute_force(model: &dyn BoatModel, time: u64, distance: u64) -> u64 {
+        (0..=time).filter(|hold| model.distance(*hold, time) > distance as u128).count() as u64
+    }
+
+    #[test]
+    fn test_models() {
+        assert_eq!(Linear::default().distance(3, 7), 12);
+        assert_eq!(Linear { rate: 2 }.distance(3, 7), 24);
+        assert_eq!(Capped { rate: 2, max_speed: 5 }.distance(3, 7), 20);
+        assert_eq!(Friction { rate: 1, friction: 1 }.distance(3, 7), 3 + 2 + 1);
+        assert_eq!(Friction { rate: 2, friction: 3 }.distance(3, 10), 6 + 3);
+        assert_eq!(Friction { rate: 1, friction: 0 }.distance(3, 7), 12);
+        assert_eq!(Friction { rate: u64::MAX, friction: 1 }.distance(u64::MAX / 2, u64::MAX), u128::MAX);
+        assert_eq!(Friction { rate: u64::MAX, friction: u64::MAX }.distance(2, 5), (2 + 1) * u64::MAX as u128);
+        assert_eq!(winways_with(&Friction { rate: u64::MAX, friction: 1 }, u64::MAX, 5), u64::MAX - 1);
+        assert_eq!(winways_with(&Linear::default(), 30, 200), 9);
+        assert_eq!(winways_with(&Linear::default(), 0, 0), 0);
+        assert_eq!(winways_with(&Linear { rate: 0 }, 10, 0), 0);
+        assert_eq!(winways_with(&Linear::default(), u64::MAX, u64::MAX), u64::MAX - 3);
+        assert!(parse_model("capped:1,30").is_ok());
+        assert!(parse_model("linear").is_ok());
+        assert_eq!(parse_model("friction:1").err(), Some(Error::FormatError));
+        assert_eq!(parse_model("warp:9").err(), Some(Error::FormatError));
+    }
+
+    fn model_strategy() -> impl Strategy<Value = Box<dyn BoatModel>> {
+        prop_oneof![
+            (0u64..5).prop_map(|rate| Box::new(Linear { rate }) as Box<dyn BoatModel>),
+            (0u64..5, 0u64..40).prop_map(|(rate, max_speed)| Box::new(Capped { rate, max_speed }) as Box<dyn BoatModel>),
+            (0u64..5, 0u64..5).prop_map(|(rate, friction)| Box::new(Friction { rate, friction }) as Box<dyn BoatModel>),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn winways_with_matches_brute_force(model in model_strategy(), time in 0u64..200, distance in 0u64..2000) {
+            prop_assert_eq!(winways_with(model.as_ref(), time, distance), brute_force(model.as_ref(), time, distance));
+        }
+    }
+}
@@ -1,32 +1,70 @@
+mod boat;
 mod input;
 mod task;
 
 use input::{read_file, read_stdin};
+use common::profile::{measure, report};
+use common::store::{read_store, record_store};
 use std::io;
 use std::env;
+use boat::parse_model;
 use task::*;
 
 enum Mode {
     File(String),
-    Stdin
+    Stdin,
+    Store(Option<String>),
 }
 
 fn main() -> io::Result<()> {
-    let mode = env::args().nth(1).map(|arg| Mode::File(arg)).unwrap_or(Mode::Stdin);
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let model = match args.iter().position(|arg| arg == "--model") {
+        Some(i) if i + 1 < args.len() => {
+            let value = args.drain(i..i + 2).nth(1).unwrap();
+            Some(parse_model(&value).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, format!("--model {}: {}", value, error)))?)
+        },
+        _ => None,
+    };
+    let mut args = args.into_iter();
+    let mode = match args.next() {
+        Some(arg) if arg == "--profile" => match args.next() {
+            Some(profile) => Mode::Store(Some(profile)),
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "--profile needs a name")),
+        },
+        Some(arg) if arg == "--record" => return record_store(env!("CARGO_PKG_NAME"), args.next().as_deref()),
+        Some(arg) if arg == "-" => Mode::Stdin,
+        Some(arg) => Mode::File(arg),
+        None => Mode::Store(None),
+    };
     let data = match mode {
         Mode::File(file_path) => read_file(&file_path)?,
-        Mode::Stdin => read_stdin()?
+        Mode::Stdin => read_stdin()?,
+        Mode::Store(profile) => match read_store(env!("CARGO_PKG_NAME"), profile.as_deref())? {
+            Some(data) => data,
+            None if profile.is_none() => read_stdin()?,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "stored input not found")),
+        },
     };
 
-    match task1(&data) {
+    let (result, usage) = measure(|| match &model {
+        Some(model) => task1_with(&data, model.as_ref()),
+        None => task1(&data),
+    });
+    match result {
         Ok(result) => println!("result1 {}", result),
         Err(error) => println!("error {}", error)
     }
+    report(env!("CARGO_PKG_NAME"), "task1", usage);
 
-    match task2(&data) {
+    let (result, usage) = measure(|| match &model {
+        Some(model) => task2_with(&data, model.as_ref()),
+        None => task2(&data),
+    });
+    match result {
         Ok(result) => println!("result2 {}", result),
         Err(error) => println!("error {}", error)
     }
+    report(env!("CARGO_PKG_NAME"), "task2", usage);
 
     Ok(())
 }
@@ -1,5 +1,6 @@
 use std::num::ParseIntError;
 use itertools::Itertools;
+use crate::boat::{winways_with, BoatModel};
 
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
@@ -23,23 +24,29 @@ fn winways_classic(time: u64, distance: u64) -> u64 {
     way
 }
 
+// holds h with h * (time - h) > distance lie strictly between the roots
+// (time ± sqrt(time² - 4 distance)) / 2, found exactly in u128
 fn winways(time: u64, distance: u64) -> u64 {
-    let mut l = 1;
-    let mut r = time / 2;
-    while l < r {
-        let m = (l + r) / 2;
-        let d = (time - m) * m;
-        if d <= distance {
-            l = m + 1;
-        } else {
-            r = m;
-        }
+    let (t, d) = (time as u128, distance as u128);
+    if t * t <= 4 * d {
+        return 0;
+    }
+    let wins = |h: u128| h * (t - h) > d;
+    let mut lo = (t - (t * t - 4 * d).isqrt()) / 2;
+    while lo <= t / 2 && !wins(lo) {
+        lo += 1;
+    }
+    if lo > t / 2 {
+        return 0;
+    }
+    while lo > 0 && wins(lo - 1) {
+        lo -= 1;
     }
-    return time + 1 - l * 2;
+    (t + 1 - 2 * lo) as u64
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    let time = lines.get(0)
+fn parse_races<S: AsRef<str>>(lines: &[S]) -> Result<Vec<(u64, u64)>> {
+    let time = lines.first()
         .ok_or(Error::FormatError)?
         .as_ref()
         .split_whitespace()
@@ -56,17 +63,12 @@ pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
     if time.len() != distance.len() {
         return Err(Error::FormatError);
     }
-    let n = time.len();
-    let mut ans = 1;
-    for attempt in 0..n {
-        ans *= winways(time[attempt], distance[attempt]);
-    }
-    Ok(ans)
+    Ok(time.into_iter().zip(distance).collect())
 }
 
-pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
+fn parse_race<S: AsRef<str>>(lines: &[S]) -> Result<(u64, u64)> {
     let time: u64 = lines
-        .get(0)
+        .first()
         .ok_or(Error::FormatError)?
         .as_ref()
         .split_whitespace()
@@ -81,12 +83,32 @@ pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
         .skip(1)
         .join("")
         .parse()?;
+    Ok((time, distance))
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
+    Ok(parse_races(lines)?.into_iter().map(|(time, distance)| winways(time, distance)).product())
+}
+
+pub fn task1_with<S: AsRef<str>, M: BoatModel + ?Sized>(lines: &[S], model: &M) -> Result<u64> {
+    Ok(parse_races(lines)?.into_iter().map(|(time, distance)| winways_with(model, time, distance)).product())
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
+    let (time, distance) = parse_race(lines)?;
     Ok(winways(time, distance))
 }
 
+pub fn task2_with<S: AsRef<str>, M: BoatModel + ?Sized>(lines: &[S], model: &M) -> Result<u64> {
+    let (time, distance) = parse_race(lines)?;
+    Ok(winways_with(model, time, distance))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::boat::{Capped, Linear};
+    use proptest::prelude::*;
     const DATA: &str =
 "Time:      7  15   30
 Distance:  9  40  200";
@@ -108,5 +130,44 @@ Distance:  9  40  200";
         assert_eq!(winways_classic(7, 9), winways(7, 9));
         assert_eq!(winways_classic(15, 40), winways(15, 40));
         assert_eq!(winways_classic(30, 200), winways(30, 200));
+        assert_eq!(winways(3, 2), 0);
+        assert_eq!(winways(4, 3), 1);
+        assert_eq!(winways(4, 4), 0);
+        assert_eq!(winways(u64::MAX, 0), u64::MAX - 1);
+        assert_eq!(winways(u64::MAX, u64::MAX), u64::MAX - 3);
+        assert_eq!(winways(1 << 32, u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_models() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        assert_eq!(Ok(288), task1_with(&lines, &Linear::default()));
+        assert_eq!(Ok(71503), task2_with(&lines, &Linear::default()));
+        assert_eq!(Ok(4 * 8 * 3), task1_with(&lines, &Capped { rate: 1, max_speed: 12 }));
+    }
+
+    proptest! {
+        #[test]
+        fn winways_matches_classic(time in 0u64..1000, distance in 0u64..250000) {
+            prop_assert_eq!(winways_classic(time, distance), winways(time, distance));
+        }
+
+        #[test]
+        fn winways_exact_at_boundary((hold, time) in (1u64..1 << 32).prop_flat_map(|hold| (Just(hold), 2 * hold + 1..=hold + u64::MAX / hold))) {
+            prop_assert_eq!(winways(time, hold * (time - hold)), time - 2 * hold - 1);
+        }
+
+        #[test]
+        fn winways_bounds_are_tight(time in any::<u64>(), distance in any::<u64>()) {
+            let ways = winways(time, distance) as u128;
+            let (t, d) = (time as u128, distance as u128);
+            if ways > 0 {
+                let lo = (t + 1 - ways) / 2;
+                prop_assert!(lo * (t - lo) > d);
+                prop_assert!((lo - 1) * (t - lo + 1) <= d);
+            } else {
+                prop_assert!((t / 2) * (t - t / 2) <= d);
+            }
+        }
     }
 }
@@ -0,0 +1,119 @@
+use std::ops::Range;
+
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn range(&mut self, range: Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+}
+
+fn vertical(from: i64, to: i64) -> (char, i64) {
+    if to < from { ('U', from - to) } else { ('D', to - from) }
+}
+
+fn pipe(a: char, b: char) -> u8 {
+    match (a.min(b), a.max(b)) {
+        ('D', 'U') => b'|',
+        ('L', 'R') => b'-',
+        ('R', 'U') => b'L',
+        ('L', 'U') => b'J',
+        ('D', 'L') => b'7',
+        ('D', 'R') => b'F',
+        _ => unreachable!(),
+    }
+}
+
+fn opposite(dir: char) -> char {
+    match dir { 'U' => 'D', 'D' => 'U', 'L' => 'R', _ => 'L' }
+}
+
+// loop cells in walking order, starting at the top left, with the step leaving each
+fn trace(rng: &mut Rng, size: usize) -> (Vec<(i64, i64)>, Vec<char>) {
+    let mut columns = Vec::new();
+    let (mut top, mut bottom) = (0, rng.range(1..6) as i64);
+    columns.push((rng.range(1..5) as i64, top, bottom));
+    for _ in 1..size.max(1) {
+        let next_top = top - 3 + rng.range(0..(bottom - top + 3) as u64) as i64;
+        let next_bottom = top.max(next_top) + rng.range(1..6) as i64;
+        columns.push((rng.range(1..5) as i64, next_top, next_bottom));
+        (top, bottom) = (next_top, next_bottom);
+    }
+    let mut moves = Vec::new();
+    for (i, (width, top, _)) in columns.iter().enumerate() {
+        moves.push(('R', *width));
+        if let Some((_, next_top, _)) = columns.get(i + 1) {
+            moves.push(vertical(*top, *next_top));
+        }
+    }
+    let (_, last_top, last_bottom) = columns[columns.len() - 1];
+    moves.push(vertical(last_top, last_bottom));
+    for (i, (width, _, bottom)) in columns.iter().enumerate().rev() {
+        moves.push(('L', *width));
+        if i > 0 {
+            moves.push(vertical(*bottom, columns[i - 1].2));
+        }
+    }
+    moves.push(vertical(columns[0].2, columns[0].1));
+
+    let steps = moves.into_iter().flat_map(|(dir, len)| std::iter::repeat_n(dir, len as usize)).collect::<Vec<_>>();
+    let mut cells = Vec::new();
+    let (mut x, mut y) = (0i64, columns[0].1);
+    for dir in &steps {
+        cells.push((x, y));
+        match dir {
+            'R' => x += 1,
+            'L' => x -= 1,
+            'U' => y -= 1,
+            _ => y += 1,
+        }
+    }
+    (cells, steps)
+}
+
+pub(crate) fn generate(size: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let (cells, steps) = trace(&mut rng, size);
+    let margin = rng.range(0..3) as i64;
+    let min_y = cells.iter().map(|c| c.1).min().unwrap() - margin;
+    let max_y = cells.iter().map(|c| c.1).max().unwrap() + margin;
+    let max_x = cells.iter().map(|c| c.0).max().unwrap() + margin;
+    let junk = b".....|-LJ7F";
+    let mut grid = (min_y..=max_y).map(|_| {
+        (-margin..=max_x).map(|_| junk[rng.range(0..junk.len() as u64) as usize]).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    for (i, (x, y)) in cells.iter().enumerate() {
+        let prev = opposite(steps[(i + steps.len() - 1) % steps.len()]);
+        grid[(y - min_y) as usize][(x + margin) as usize] = if i == 0 { b'S' } else { pipe(prev, steps[i]) };
+    }
+    grid.into_iter().map(|row| String::from_utf8(row).unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::task1;
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..20 {
+            for size in [1, 5, 20] {
+                let (_, steps) = trace(&mut Rng::new(seed), size);
+                assert_eq!(Ok(steps.len() as i32 / 2), task1(&generate(size, seed)));
+            }
+        }
+        assert_eq!(generate(5, 3), generate(5, 3));
+    }
+}
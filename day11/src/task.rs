@@ -79,6 +79,7 @@ pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     const DATA: &str =
 "...#......
 .......#..
@@ -103,4 +104,39 @@ mod tests {
         assert_eq!(Ok(1030), expand(&lines, 10));
         assert_eq!(Ok(8410), expand(&lines, 100));
     }
+
+    fn explicit_expand(grid: &[Vec<bool>], multiplier: usize) -> usize {
+        let m = grid[0].len();
+        let empty_cols = (0..m).map(|j| grid.iter().all(|row| !row[j])).collect::<Vec<_>>();
+        let mut expanded = Vec::new();
+        for row in grid {
+            let line = row.iter().zip(&empty_cols)
+                .flat_map(|(galaxy, empty)| vec![*galaxy; if *empty { multiplier } else { 1 }])
+                .collect::<Vec<_>>();
+            let copies = if row.iter().any(|g| *g) { 1 } else { multiplier };
+            expanded.extend(vec![line; copies]);
+        }
+        let galaxies = expanded.iter().enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate().filter(|(_, g)| **g).map(move |(j, _)| (i, j)))
+            .collect::<Vec<_>>();
+        let mut total = 0;
+        for (k, a) in galaxies.iter().enumerate() {
+            for b in &galaxies[k + 1..] {
+                total += a.0.abs_diff(b.0) + a.1.abs_diff(b.1);
+            }
+        }
+        total
+    }
+
+    fn grid_strategy() -> impl Strategy<Value = Vec<Vec<bool>>> {
+        (1usize..8, 1usize..8).prop_flat_map(|(n, m)| prop::collection::vec(prop::collection::vec(prop::bool::weighted(0.2), m), n))
+    }
+
+    proptest! {
+        #[test]
+        fn expand_matches_explicit(grid in grid_strategy(), multiplier in 2usize..10) {
+            let lines = grid.iter().map(|row| row.iter().map(|g| if *g { '#' } else { '.' }).collect::<String>()).collect::<Vec<_>>();
+            prop_assert_eq!(Ok(explicit_expand(&grid, multiplier)), expand(&lines, multiplier));
+        }
+    }
 }
@@ -0,0 +1,38 @@
+use std::ops::Range;
+
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn range(&mut self, range: Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+}
+
+pub(crate) fn generate(size: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    (0..size.max(1)).map(|_| {
+        let groups = (0..rng.range(1..5)).map(|_| rng.range(1..5) as usize).collect::<Vec<_>>();
+        let mut springs = ".".repeat(rng.range(0..3) as usize);
+        for (i, len) in groups.iter().enumerate() {
+            if i > 0 {
+                springs.push_str(&".".repeat(rng.range(1..4) as usize));
+            }
+            springs.push_str(&"#".repeat(*len));
+        }
+        springs.push_str(&".".repeat(rng.range(0..3) as usize));
+        let masked = springs.chars().map(|c| if rng.range(0..2) == 0 { '?' } else { c }).collect::<String>();
+        format!("{} {}", masked, groups.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(","))
+    }).collect()
+}
@@ -91,6 +91,8 @@ pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::generate;
+    use proptest::prelude::*;
     const DATA: &str =
 "???.### 1,1,3
 .??..??...?##. 1,1,3
@@ -116,4 +118,63 @@ mod tests {
         let (s, n) = parse_line("?###???????? 3,2,1", 1).unwrap();
         assert_eq!(Some(10), arrangement(s, n, &mut HashMap::new()));
     }
+
+    fn groups(springs: &[u8]) -> Vec<u32> {
+        springs.split(|b| *b == b'.').filter(|g| !g.is_empty()).map(|g| g.len() as u32).collect()
+    }
+
+    fn enumerate_arrangements(springs: &[u8], nums: &[u32]) -> u64 {
+        let unknown = springs.iter().enumerate().filter(|(_, b)| **b == b'?').map(|(i, _)| i).collect::<Vec<_>>();
+        let mut count = 0;
+        for mask in 0u32..1 << unknown.len() {
+            let mut candidate = springs.to_vec();
+            for (bit, i) in unknown.iter().enumerate() {
+                candidate[*i] = if mask & (1 << bit) != 0 { b'#' } else { b'.' };
+            }
+            if groups(&candidate) == nums {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    fn row_strategy(max_len: usize) -> impl Strategy<Value = String> {
+        (
+            prop::collection::vec(prop::sample::select(vec!['.', '#', '?', '?']), 1..max_len),
+            prop::collection::vec(1u32..4, 1..4),
+        ).prop_map(|(springs, nums)| format!("{} {}", springs.into_iter().collect::<String>(), nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",")))
+    }
+
+    fn generated_rows_strategy(max_unknown: usize) -> impl Strategy<Value = Vec<String>> {
+        (1usize..5, any::<u64>()).prop_map(move |(size, seed)| {
+            generate(size, seed).into_iter().filter(|line| line.matches('?').count() <= max_unknown).collect::<Vec<_>>()
+        }).prop_filter("no row small enough to enumerate", |lines| !lines.is_empty())
+    }
+
+    proptest! {
+        #[test]
+        fn arrangement_matches_enumeration(line in row_strategy(14)) {
+            let (springs, nums) = parse_line(&line, 1).unwrap();
+            let expected = enumerate_arrangements(&springs, &nums);
+            prop_assert_eq!(expected, arrangement(springs, nums, &mut HashMap::new()).unwrap_or(0));
+        }
+
+        #[test]
+        fn unfolded_arrangement_matches_enumeration(line in row_strategy(6)) {
+            let (springs, nums) = parse_line(&line, 2).unwrap();
+            let expected = enumerate_arrangements(&springs, &nums);
+            prop_assert_eq!(expected, arrangement(springs, nums, &mut HashMap::new()).unwrap_or(0));
+        }
+
+        // generated rows always have at least one arrangement
+        #[test]
+        fn generated_arrangement_matches_enumeration(lines in generated_rows_strategy(12)) {
+            for line in lines {
+                let (springs, nums) = parse_line(&line, 1).unwrap();
+                let expected = enumerate_arrangements(&springs, &nums);
+                prop_assert!(expected > 0);
+                prop_assert_eq!(Some(expected), arrangement(springs, nums, &mut HashMap::new()));
+            }
+        }
+    }
 }
@@ -0,0 +1,68 @@
+use std::ops::Range;
+
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn range(&mut self, range: Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+}
+
+fn vertical(from: i64, to: i64) -> Option<(char, i64)> {
+    match to.cmp(&from) {
+        std::cmp::Ordering::Greater => Some(('U', to - from)),
+        std::cmp::Ordering::Less => Some(('D', from - to)),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+fn plan(size: usize, rng: &mut Rng) -> Vec<(char, i64)> {
+    let mut columns = Vec::new();
+    let (mut bottom, mut top) = (0, rng.range(1..6) as i64);
+    columns.push((rng.range(1..5) as i64, bottom, top));
+    for _ in 1..size.max(1) {
+        let next_bottom = bottom - 3 + rng.range(0..(top - bottom + 3) as u64) as i64;
+        let next_top = bottom.max(next_bottom) + rng.range(1..6) as i64;
+        columns.push((rng.range(1..5) as i64, next_bottom, next_top));
+        (bottom, top) = (next_bottom, next_top);
+    }
+    let mut plan = Vec::new();
+    for (i, (width, bottom, _)) in columns.iter().enumerate() {
+        plan.push(('R', *width));
+        if let Some((_, next_bottom, _)) = columns.get(i + 1) {
+            plan.extend(vertical(*bottom, *next_bottom));
+        }
+    }
+    let (_, last_bottom, last_top) = columns[columns.len() - 1];
+    plan.extend(vertical(last_bottom, last_top));
+    for (i, (width, _, top)) in columns.iter().enumerate().rev() {
+        plan.push(('L', *width));
+        if i > 0 {
+            plan.extend(vertical(*top, columns[i - 1].2));
+        }
+    }
+    plan.extend(vertical(columns[0].2, columns[0].1));
+    plan
+}
+
+pub(crate) fn generate(size: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let plan = plan(size, &mut rng);
+    let scale = rng.range(1000..20000) as i64;
+    plan.into_iter().map(|(dir, len)| {
+        let code = match dir { 'R' => 0, 'D' => 1, 'L' => 2, _ => 3 };
+        format!("{} {} (#{:05x}{})", dir, len, len * scale, code)
+    }).collect()
+}
@@ -123,6 +123,9 @@ pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::generate;
+    use proptest::prelude::*;
+    use std::collections::{HashSet, VecDeque};
     const DATA: &str =
 r#"R 6 (#70c710)
 D 5 (#0dc571)
@@ -150,4 +153,46 @@ U 2 (#7a21e3)"#;
         let lines = DATA.lines().collect::<Vec<_>>();
         assert_eq!(Ok(952408144115), task2(&lines));
     }
+
+    fn flood_fill(plan: &[(char, i64)]) -> i64 {
+        let mut trench = HashSet::from([(0, 0)]);
+        let mut p = (0i64, 0i64);
+        for (dir, len) in plan {
+            let step = match dir { 'R' => (1, 0), 'L' => (-1, 0), 'U' => (0, 1), _ => (0, -1) };
+            for _ in 0..*len {
+                p = (p.0 + step.0, p.1 + step.1);
+                trench.insert(p);
+            }
+        }
+        let min_x = trench.iter().map(|p| p.0).min().unwrap() - 1;
+        let max_x = trench.iter().map(|p| p.0).max().unwrap() + 1;
+        let min_y = trench.iter().map(|p| p.1).min().unwrap() - 1;
+        let max_y = trench.iter().map(|p| p.1).max().unwrap() + 1;
+        let mut outside = HashSet::from([(min_x, min_y)]);
+        let mut queue = VecDeque::from([(min_x, min_y)]);
+        while let Some((x, y)) = queue.pop_front() {
+            for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                if (min_x..=max_x).contains(&next.0) && (min_y..=max_y).contains(&next.1)
+                    && !trench.contains(&next) && outside.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        (max_x - min_x + 1) * (max_y - min_y + 1) - outside.len() as i64
+    }
+
+    fn plan_strategy() -> impl Strategy<Value = Vec<String>> {
+        (1usize..8, any::<u64>()).prop_map(|(size, seed)| generate(size, seed))
+    }
+
+    proptest! {
+        #[test]
+        fn task1_matches_flood_fill(lines in plan_strategy()) {
+            let plan = lines.iter().map(|line| {
+                let mut parts = line.split_ascii_whitespace();
+                (parts.next().unwrap().chars().next().unwrap(), parts.next().unwrap().parse().unwrap())
+            }).collect::<Vec<_>>();
+            prop_assert_eq!(Ok(flood_fill(&plan)), task1(&lines));
+        }
+    }
 }
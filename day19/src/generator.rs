@@ -0,0 +1,53 @@
+use std::ops::Range;
+
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn range(&mut self, range: Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+}
+
+const XMAS: [char; 4] = ['x', 'm', 'a', 's'];
+
+fn workflow_name(i: usize) -> String {
+    if i == 0 { "in".to_string() } else { format!("w{}", i) }
+}
+
+fn target_name(i: usize, n: usize, rng: &mut Rng) -> String {
+    match rng.range(0..(n - i + 1) as u64) as usize {
+        0 => "A".to_string(),
+        1 => "R".to_string(),
+        k => workflow_name(i + k - 1),
+    }
+}
+
+pub(crate) fn generate(size: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let n = size.max(1);
+    let mut lines = (0..n).map(|i| {
+        let rules = (0..rng.range(1..4)).map(|_| {
+            let xmas = XMAS[rng.range(0..4) as usize];
+            let op = if rng.range(0..2) == 0 { '<' } else { '>' };
+            format!("{}{}{}:{}", xmas, op, rng.range(1..4000), target_name(i, n, &mut rng))
+        }).collect::<Vec<_>>();
+        format!("{}{{{},{}}}", workflow_name(i), rules.join(","), target_name(i, n, &mut rng))
+    }).collect::<Vec<_>>();
+    lines.push(String::new());
+    lines.extend((0..n).map(|_| {
+        format!("{{x={},m={},a={},s={}}}", rng.range(1..4001), rng.range(1..4001), rng.range(1..4001), rng.range(1..4001))
+    }));
+    lines
+}
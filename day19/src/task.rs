@@ -234,6 +234,8 @@ pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::generate;
+    use proptest::prelude::*;
     const DATA: &str =
 r#"px{a<2006:qkq,m>2090:A,rfg}
 pv{a>1716:R,A}
@@ -264,4 +266,45 @@ hdj{m>838:A,pv}
         let lines = DATA.lines().collect::<Vec<_>>();
         assert_eq!(Ok(167409079868000), task2(&lines));
     }
+
+    fn per_part_count(lines: &[String]) -> u64 {
+        let commands = parse_commands(lines);
+        let mut bounds = vec![vec![1, MAX_RANGE]; 4];
+        for cmd in commands.values().flatten() {
+            match cmd {
+                Command::Less(xmas, val, _) => bounds[*xmas as usize].push(*val),
+                Command::Greater(xmas, val, _) => bounds[*xmas as usize].push(*val + 1),
+                Command::Default(_) => {}
+            }
+        }
+        let cells = bounds.into_iter().map(|mut b| {
+            b.sort();
+            b.dedup();
+            b.windows(2).map(|w| (w[0], (w[1] - w[0]) as u64)).collect::<Vec<_>>()
+        }).collect::<Vec<_>>();
+        let mut total = 0;
+        for (x, xn) in &cells[0] {
+            for (m, mn) in &cells[1] {
+                for (a, an) in &cells[2] {
+                    for (s, sn) in &cells[3] {
+                        let part = Part { x: *x, m: *m, a: *a, s: *s };
+                        if eval(&commands, &Target::Workflow("in".to_string()), part) > 0 {
+                            total += xn * mn * an * sn;
+                        }
+                    }
+                }
+            }
+        }
+        total
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+        #[test]
+        fn task2_matches_per_part(size in 1usize..6, seed in any::<u64>()) {
+            let lines = generate(size, seed);
+            let workflows = lines.split(|line| line.is_empty()).next().unwrap();
+            prop_assert_eq!(Ok(per_part_count(workflows)), task2(&lines));
+        }
+    }
 }
@@ -0,0 +1,85 @@
+use std::ops::Range;
+
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn range(&mut self, range: Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i as u64 + 1) as usize);
+        }
+    }
+}
+
+const INVERTERS: [&str; 4] = ["kk", "xr", "fv", "vt"];
+const RESERVED: [&str; 6] = ["kk", "xr", "fv", "vt", "sq", "rx"];
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+// distinct primes, as in real inputs, so the cycles first line up at their product;
+// at least 5 bits leaves enough primes to pick from
+pub(crate) fn periods(size: usize, rng: &mut Rng) -> Vec<u64> {
+    let bits = size.clamp(5, 12) as u32;
+    let mut primes = ((1 << (bits - 1)) + 1..1 << bits).filter(|n| is_prime(*n)).collect::<Vec<_>>();
+    rng.shuffle(&mut primes);
+    primes.truncate(INVERTERS.len());
+    primes
+}
+
+fn fresh_name(rng: &mut Rng, used: &mut Vec<String>) -> String {
+    loop {
+        let name = (0..2).map(|_| (b'a' + rng.range(0..26) as u8) as char).collect::<String>();
+        if !RESERVED.contains(&name.as_str()) && !used.contains(&name) {
+            used.push(name.clone());
+            return name;
+        }
+    }
+}
+
+pub(crate) fn generate(size: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let periods = periods(size, &mut rng);
+    let mut used = Vec::new();
+    let mut lines = Vec::new();
+    let mut starts = Vec::new();
+    for (period, inverter) in periods.iter().zip(INVERTERS) {
+        let bits = 64 - period.leading_zeros() as usize;
+        let hub = fresh_name(&mut rng, &mut used);
+        let flip_flops = (0..bits).map(|_| fresh_name(&mut rng, &mut used)).collect::<Vec<_>>();
+        let mut hub_outputs = vec![flip_flops[0].clone()];
+        for (i, name) in flip_flops.iter().enumerate() {
+            let mut outputs = flip_flops.get(i + 1).into_iter().cloned().collect::<Vec<_>>();
+            if period & (1 << i) != 0 {
+                outputs.push(hub.clone());
+            } else {
+                hub_outputs.push(name.clone());
+            }
+            lines.push(format!("%{} -> {}", name, outputs.join(", ")));
+        }
+        hub_outputs.push(inverter.to_string());
+        lines.push(format!("&{} -> {}", hub, hub_outputs.join(", ")));
+        lines.push(format!("&{} -> sq", inverter));
+        starts.push(flip_flops[0].clone());
+    }
+    lines.push("&sq -> rx".to_string());
+    lines.push(format!("broadcaster -> {}", starts.join(", ")));
+    rng.shuffle(&mut lines);
+    lines
+}
@@ -131,6 +131,7 @@ pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::{generate, periods, Rng};
     const DATA: &str =
 r#"broadcaster -> a
 %a -> inv, con
@@ -143,4 +144,14 @@ r#"broadcaster -> a
         let lines = DATA.lines().collect::<Vec<_>>();
         assert_eq!(Ok(11687500), task1(&lines));
     }
+
+    #[test]
+    fn test_generated_task2() {
+        for (size, seed) in [(5, 0), (8, 0), (8, 1), (8, 2), (12, 3)] {
+            let periods = periods(size, &mut Rng::new(seed));
+            assert!(periods.iter().enumerate().all(|(i, p)| !periods[..i].contains(p)));
+            let expected = periods.iter().product::<u64>();
+            assert_eq!(Ok(expected), task2(&generate(size, seed)));
+        }
+    }
 }